@@ -13,6 +13,7 @@ pub enum ButtonKey {
     ExploreExit,
     EnterEvent,
     LeaveEvent,
+    Choice(usize),
     GameOver,
 }
 
@@ -57,7 +58,9 @@ impl Plugin for GamehudPlugin {
 
         app.add_system_set(SystemSet::on_exit(base_mode).with_system(exit_state));
         app.add_system_set(SystemSet::on_pause(base_mode).with_system(exit_state));
-        app.add_system_set(SystemSet::on_enter(base_mode).with_system(enter_base_gameplay));
+        app.add_system_set(
+            SystemSet::on_enter(base_mode).with_system(enter_base_gameplay.after("seed-input")),
+        );
         app.add_system_set(SystemSet::on_resume(base_mode).with_system(enter_base_gameplay));
 
         app.add_system_set(SystemSet::on_exit(event_mode).with_system(exit_state));
@@ -98,6 +101,7 @@ fn exit_gameover(mut commands: Commands, query: Query<Entity, With<HudCleanup>>)
 fn enter_gameove(
     mut commands: Commands,
     app_assets: Res<AppAssets>,
+    seed: Res<RunSeed>,
     mut player_query: Query<&mut Player>,
 ) {
     log::info!("enter_gameove");
@@ -134,6 +138,19 @@ fn enter_gameove(
         },
     ));
 
+    list.push(
+        commands
+            .spawn_bundle(TextBundle::from_section(
+                format!("Seed: {}", seed.text),
+                TextStyle {
+                    font: app_assets.gui_font.clone(),
+                    font_size: 24.0,
+                    color: gui::TEXT_BUTTON,
+                },
+            ))
+            .id(),
+    );
+
     list.push(
         commands
             .spawn_bundle(TextBundle::from_section(
@@ -210,6 +227,7 @@ fn button_update(
     mut state: ResMut<State<AppState>>,
     mut buttons: ResMut<Input<MouseButton>>,
     mut hex_event: EventWriter<EndHexEvent>,
+    mut choice_event: EventWriter<ChoiceEvent>,
     diag_query: Query<&Children, With<HudDialog>>,
     navi_query: Query<&Children, With<HudNavigate>>,
     mut button_query: Query<
@@ -238,6 +256,7 @@ fn button_update(
                     navi,
                     &mut state,
                     &mut hex_event,
+                    &mut choice_event,
                 );
             }
             Interaction::Hovered => {
@@ -259,6 +278,7 @@ fn handle_btn_update_click(
     navi_children: Option<&Children>,
     state: &mut ResMut<State<AppState>>,
     hex_event: &mut EventWriter<EndHexEvent>,
+    choice_event: &mut EventWriter<ChoiceEvent>,
 ) {
     match button_key {
         ButtonKey::BaseExit => {
@@ -305,6 +325,10 @@ fn handle_btn_update_click(
             }
             hex_event.send(EndHexEvent { enter: false });
         }
+        ButtonKey::Choice(index) => {
+            log::info!("{} {} {}", KEY_CLICKED, "Choice", index);
+            choice_event.send(ChoiceEvent { index });
+        }
         ButtonKey::GameOver => {
             log::info!("{} {}", KEY_CLICKED, "GameOver");
             state
@@ -316,6 +340,7 @@ fn handle_btn_update_click(
 
 fn enter_base_gameplay(
     mut commands: Commands,
+    seed: Res<RunSeed>,
     app_assets: Res<AppAssets>,
     world_assets: Res<WorldAssets>,
 ) {
@@ -338,6 +363,7 @@ fn enter_base_gameplay(
         .id();
 
     let body = base_mode_select(&mut commands, &app_assets, &world_assets);
+    let input = base_mode_seed(&mut commands, &app_assets, &seed);
 
     let menu = commands
         .spawn_bundle(NodeBundle {
@@ -372,7 +398,7 @@ fn enter_base_gameplay(
 
     commands.entity(menu).push_children(&list);
 
-    commands.entity(root).push_children(&[menu, body]);
+    commands.entity(root).push_children(&[menu, body, input]);
 }
 
 fn enter_event_gameplay(
@@ -453,7 +479,7 @@ fn enter_event_gameplay(
     commands.entity(root).push_children(&[menu, body]);
 }
 
-fn enter_explore_gameplay(mut commands: Commands, app_assets: Res<AppAssets>) {
+fn enter_explore_gameplay(mut commands: Commands, seed: Res<RunSeed>, app_assets: Res<AppAssets>) {
     log::info!("enter_explore_gameplay");
 
     let root = commands
@@ -519,6 +545,22 @@ fn enter_explore_gameplay(mut commands: Commands, app_assets: Res<AppAssets>) {
             key: ButtonKey::ExploreExit,
         },
     );
+    let label = commands
+        .spawn_bundle(TextBundle {
+            style: Style {
+                margin: UiRect::new(Val::Px(12.), Val::Px(0.), Val::Px(0.), Val::Px(0.)),
+                ..default()
+            },
+            ..TextBundle::from_section(
+                format!("seed: {}", seed.text),
+                TextStyle {
+                    font: app_assets.gui_font.clone(),
+                    font_size: 24.0,
+                    color: gui::TEXT_BUTTON,
+                },
+            )
+        })
+        .id();
     let left = commands
         .spawn_bundle(NodeBundle {
             style: Style {
@@ -533,7 +575,7 @@ fn enter_explore_gameplay(mut commands: Commands, app_assets: Res<AppAssets>) {
             color: Color::NONE.into(),
             ..default()
         })
-        .push_children(&[exit])
+        .push_children(&[exit, label])
         .id();
 
     let stats = explore_mode_stats(&mut commands, &app_assets.gui_font);
@@ -1,7 +1,7 @@
 use bevy::prelude::*;
 use bevy::render::camera::RenderTarget;
 use bevy::render::texture::ImageSettings;
-use bevy::utils::HashMap;
+use bevy::utils::{HashMap, HashSet};
 use bevy::{log, window};
 
 use bevy_asset_loader::prelude::*;
@@ -1,10 +1,27 @@
+use std::f64::consts::PI;
+use std::ops::Range;
+
 use byteorder::{BigEndian, ByteOrder};
+use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256};
 
+// Note: The state is the whole generator, saving it resumes the exact stream
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Shift64 {
     seed: i64,
 }
 
+// Independent generators for each subsystem, derived from the run seed.
+// Every stream is owned by a fixed set of systems that run in a known
+// order, so draws in one subsystem never shift the values of another.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RngStreams {
+    pub map: Shift64,      // Galaxy layout, grid nodes and points
+    pub event: Shift64,    // Event rolls when arriving on a hex
+    pub combat: Shift64,   // Enemy placement and fire timing
+    pub cosmetic: Shift64, // Background effects, never gameplay
+}
+
 impl Shift64 {
     pub fn new(mut seed: i64) -> Self {
         seed += 1;
@@ -17,6 +34,19 @@ impl Shift64 {
         };
     }
 
+    pub fn from_state(state: i64) -> Self {
+        Self { seed: state }
+    }
+
+    pub fn state(&self) -> i64 {
+        self.seed
+    }
+
+    // Child generator seeded from the next value, the parent advances once
+    pub fn fork(&mut self) -> Shift64 {
+        Shift64::new(self.shift())
+    }
+
     pub fn shift(&mut self) -> i64 {
         self.seed ^= self.seed >> 12;
         self.seed ^= self.seed << 25;
@@ -26,19 +56,127 @@ impl Shift64 {
         return self.seed;
     }
 
+    // Uniform in -1..1
+    pub fn signed(&mut self) -> f32 {
+        ((self.shift() as f64) / 9223372036854775808.0) as f32
+    }
+
+    // Uniform in 0..1
+    pub fn unit(&mut self) -> f64 {
+        ((self.shift() as u64) >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    // Uniform in 0..n, rejects the few values that would favour low results
+    pub fn below(&mut self, n: u64) -> u64 {
+        if n == 0 {
+            return 0;
+        }
+        let threshold = n.wrapping_neg() % n;
+        loop {
+            let m = (self.shift() as u64 as u128) * (n as u128);
+            if (m as u64) >= threshold {
+                return (m >> 64) as u64;
+            }
+        }
+    }
+
+    // Note: An empty range returns its start instead of panicking
+    pub fn range<T: SampleRange>(&mut self, range: Range<T>) -> T {
+        T::sample(self, range)
+    }
+
     pub fn f32(&mut self, n: f32) -> f32 {
-        let x = (self.shift() as f64) / 9223372036854775808.0;
-        return ((x * n as f64).abs() - 1.0) as f32;
+        self.range(0.0..n)
     }
 
     pub fn i32(&mut self, n: i32) -> i32 {
-        let x = (self.shift() as f64) / 9223372036854775808.0;
-        return ((x * n as f64).abs() - 1.0) as i32;
+        self.range(0..n)
     }
 
     pub fn usize(&mut self, n: usize) -> usize {
-        let x = (self.shift() as f64) / 9223372036854775808.0;
-        return ((x * n as f64).abs() - 1.0) as usize;
+        self.range(0..n)
+    }
+
+    pub fn bool(&mut self, p: f32) -> bool {
+        self.unit() < p as f64
+    }
+
+    // True in n out of d cases
+    pub fn chance(&mut self, n: u32, d: u32) -> bool {
+        self.below(d as u64) < n as u64
+    }
+
+    // Index picked in proportion to its weight, None if all weights are zero
+    pub fn weighted(&mut self, weights: &[u32]) -> Option<usize> {
+        let total: u64 = weights.iter().map(|w| *w as u64).sum();
+        if total == 0 {
+            return None;
+        }
+        let mut roll = self.below(total);
+        for (idx, w) in weights.iter().enumerate() {
+            if roll < *w as u64 {
+                return Some(idx);
+            }
+            roll -= *w as u64;
+        }
+        None
+    }
+
+    pub fn shuffle<T>(&mut self, items: &mut [T]) {
+        for i in (1..items.len()).rev() {
+            let j = self.below(i as u64 + 1) as usize;
+            items.swap(i, j);
+        }
+    }
+
+    // Normal distribution with the Box-Muller transform
+    pub fn gaussian(&mut self, mean: f32, deviation: f32) -> f32 {
+        let u1 = 1.0 - self.unit();
+        let u2 = self.unit();
+        let z = (-2.0 * u1.ln()).sqrt() * (2.0 * PI * u2).cos();
+        mean + deviation * z as f32
+    }
+}
+
+pub trait SampleRange: Sized {
+    fn sample(rng: &mut Shift64, range: Range<Self>) -> Self;
+}
+
+macro_rules! sample_int {
+    ($($t:ty),*) => {$(
+        impl SampleRange for $t {
+            fn sample(rng: &mut Shift64, range: Range<Self>) -> Self {
+                if range.end <= range.start {
+                    return range.start;
+                }
+                let span = (range.end as i128 - range.start as i128) as u64;
+                (range.start as i128 + rng.below(span) as i128) as $t
+            }
+        }
+    )*};
+}
+
+sample_int!(i32, i64, u32, u64, usize);
+
+impl SampleRange for f32 {
+    fn sample(rng: &mut Shift64, range: Range<Self>) -> Self {
+        let v = range.start + (range.end - range.start) * rng.unit() as f32;
+        // Note: Rounding may land on the end, which is not part of the range
+        match v < range.end {
+            true => v,
+            false => range.start,
+        }
+    }
+}
+
+impl RngStreams {
+    pub fn new(seed: i64) -> Self {
+        Self {
+            map: Shift64::new(get_seed(format!("{seed}/map"))),
+            event: Shift64::new(get_seed(format!("{seed}/event"))),
+            combat: Shift64::new(get_seed(format!("{seed}/combat"))),
+            cosmetic: Shift64::new(get_seed(format!("{seed}/cosmetic"))),
+        }
     }
 }
 
@@ -55,7 +193,9 @@ pub fn get_seed(str: String) -> i64 {
 
 #[cfg(test)]
 mod tests {
-    use super::{get_seed, Shift64};
+    use super::{get_seed, RngStreams, Shift64};
+
+    const SAMPLES: usize = 100_000;
 
     #[test]
     fn shift64_i32() {
@@ -67,19 +207,19 @@ mod tests {
         assert_eq!(shift_a.shift(), 1075437695011947220);
         assert_eq!(shift_a.shift(), -930821246400571898);
 
-        assert_eq!(shift_a.i32(2048), 1025);
-        assert_eq!(shift_a.i32(1024), 798);
-        assert_eq!(shift_a.i32(512), 235);
-        assert_eq!(shift_a.i32(256), 205);
+        assert_eq!(shift_a.i32(2048), 513);
+        assert_eq!(shift_a.i32(1024), 399);
+        assert_eq!(shift_a.i32(512), 118);
+        assert_eq!(shift_a.i32(256), 103);
 
-        assert_eq!(shift_a.i32(128), 108);
+        assert_eq!(shift_a.i32(128), 73);
         assert_eq!(shift_a.i32(64), 42);
-        assert_eq!(shift_a.i32(128), 30);
+        assert_eq!(shift_a.i32(128), 112);
 
-        assert_eq!(shift_a.i32(256), 185);
-        assert_eq!(shift_a.i32(512), 237);
-        assert_eq!(shift_a.i32(1024), 974);
-        assert_eq!(shift_a.i32(2048), 1385);
+        assert_eq!(shift_a.i32(256), 93);
+        assert_eq!(shift_a.i32(512), 119);
+        assert_eq!(shift_a.i32(1024), 487);
+        assert_eq!(shift_a.i32(2048), 693);
 
         assert_eq!(shift_a.shift(), -5828336445164884370);
         assert_eq!(shift_a.shift(), 1599167847083165552);
@@ -101,4 +241,147 @@ mod tests {
         assert_eq!(d, 1883749424214749104);
         assert_eq!(e, -4661580130154814320);
     }
+
+    #[test]
+    fn rng_streams() {
+        let mut a = RngStreams::new(42);
+        let mut b = RngStreams::new(42);
+
+        // Draws from one stream leave the others untouched
+        for _ in 0..16 {
+            a.combat.shift();
+            a.cosmetic.shift();
+        }
+        assert_eq!(a.map.shift(), b.map.shift());
+        assert_eq!(a.event.shift(), b.event.shift());
+        assert_ne!(a.map.shift(), a.event.shift());
+
+        let mut c = RngStreams::new(43);
+        assert_ne!(c.map.shift(), b.map.shift());
+    }
+
+    #[test]
+    fn shift64_range() {
+        let mut rng = Shift64::new(7);
+        let mut counts = [0usize; 10];
+        for _ in 0..SAMPLES {
+            let v = rng.range(0..10);
+            assert!((0..10).contains(&v));
+            counts[v as usize] += 1;
+        }
+
+        // Chi-squared with 9 degrees of freedom, 27.88 is p = 0.001
+        let expected = SAMPLES as f64 / 10.;
+        let chi: f64 = counts
+            .iter()
+            .map(|c| (*c as f64 - expected).powi(2) / expected)
+            .sum();
+        assert!(chi < 27.88, "chi squared {}", chi);
+
+        for _ in 0..1000 {
+            let v = rng.range(-5..5);
+            assert!((-5..5).contains(&v));
+            let f = rng.range(1.5..2.5);
+            assert!((1.5..2.5).contains(&f));
+            assert!(rng.range(i64::MIN..i64::MAX) < i64::MAX);
+        }
+        assert_eq!(rng.range(3..3), 3);
+        assert_eq!(rng.i32(0), 0);
+    }
+
+    #[test]
+    fn shift64_bool_chance() {
+        let mut rng = Shift64::new(11);
+        let hits = (0..SAMPLES).filter(|_| rng.bool(0.25)).count();
+        assert!((hits as f64 / SAMPLES as f64 - 0.25).abs() < 0.01);
+
+        let hits = (0..SAMPLES).filter(|_| rng.chance(1, 3)).count();
+        assert!((hits as f64 / SAMPLES as f64 - 1. / 3.).abs() < 0.01);
+
+        assert!(!(0..100).any(|_| rng.bool(0.)));
+        assert!((0..100).all(|_| rng.chance(4, 4)));
+    }
+
+    #[test]
+    fn shift64_weighted() {
+        let mut rng = Shift64::new(13);
+        let weights = [1, 0, 3, 6];
+        let mut counts = [0usize; 4];
+        for _ in 0..SAMPLES {
+            counts[rng.weighted(&weights).unwrap()] += 1;
+        }
+        assert_eq!(counts[1], 0);
+        for (idx, w) in weights.iter().enumerate() {
+            let share = counts[idx] as f64 / SAMPLES as f64;
+            assert!((share - *w as f64 / 10.).abs() < 0.01);
+        }
+        assert_eq!(rng.weighted(&[0, 0]), None);
+        assert_eq!(rng.weighted(&[]), None);
+    }
+
+    #[test]
+    fn shift64_shuffle() {
+        let mut rng = Shift64::new(17);
+        let mut first = [0usize; 5];
+        for _ in 0..SAMPLES / 10 {
+            let mut items = [0, 1, 2, 3, 4];
+            rng.shuffle(&mut items);
+            let mut sorted = items;
+            sorted.sort();
+            assert_eq!(sorted, [0, 1, 2, 3, 4]);
+            first[items[0]] += 1;
+        }
+        for count in first {
+            let share = count as f64 / (SAMPLES / 10) as f64;
+            assert!((share - 0.2).abs() < 0.02);
+        }
+    }
+
+    #[test]
+    fn shift64_gaussian() {
+        let mut rng = Shift64::new(19);
+        let values: Vec<f32> = (0..SAMPLES).map(|_| rng.gaussian(4., 2.)).collect();
+        let mean = values.iter().sum::<f32>() / SAMPLES as f32;
+        let var = values.iter().map(|v| (v - mean).powi(2)).sum::<f32>() / SAMPLES as f32;
+        assert!((mean - 4.).abs() < 0.05, "mean {}", mean);
+        assert!((var.sqrt() - 2.).abs() < 0.05, "deviation {}", var.sqrt());
+    }
+
+    #[test]
+    fn shift64_state() {
+        let mut rng = Shift64::new(23);
+        rng.range(0..100);
+
+        let saved = ron::to_string(&rng).unwrap();
+        let mut restored: Shift64 = ron::from_str(&saved).unwrap();
+        let mut copy = Shift64::from_state(rng.state());
+        for _ in 0..8 {
+            let v = rng.shift();
+            assert_eq!(restored.shift(), v);
+            assert_eq!(copy.shift(), v);
+        }
+
+        let streams = RngStreams::new(5);
+        let saved = ron::to_string(&streams).unwrap();
+        assert_eq!(ron::from_str::<RngStreams>(&saved).unwrap(), streams);
+    }
+
+    #[test]
+    fn shift64_fork() {
+        let mut a = Shift64::new(29);
+        let mut b = Shift64::new(29);
+        let mut child_a = a.fork();
+        let mut child_b = b.fork();
+
+        // Forks are reproducible and do not repeat the parent stream
+        assert_eq!(child_a, child_b);
+        assert_eq!(a, b);
+        let parent: Vec<i64> = (0..8).map(|_| a.shift()).collect();
+        for _ in 0..8 {
+            let v = child_a.shift();
+            assert_eq!(child_b.shift(), v);
+            assert!(!parent.contains(&v));
+        }
+        assert_ne!(a.fork(), child_a.fork());
+    }
 }
@@ -12,6 +12,9 @@ pub use mode::*;
 pub mod player;
 pub use player::*;
 
+pub mod story;
+pub use story::*;
+
 pub const TILE_SIZE: f32 = 64.0;
 
 pub const ROTATE_SPEED: f32 = 24.0;
@@ -46,6 +49,16 @@ pub struct WorldAssets {
     ship_7: Handle<Image>,
     #[asset(path = "Ship-17.png")]
     ship_17: Handle<Image>,
+    #[asset(path = "galaxy/default.galaxy.ron")]
+    galaxy: Handle<GalaxyAsset>,
+    #[asset(path = "events/combat.events.ron")]
+    combat_events: Handle<EventTable>,
+    #[asset(path = "events/energy.events.ron")]
+    energy_events: Handle<EventTable>,
+    #[asset(path = "events/mining.events.ron")]
+    mining_events: Handle<EventTable>,
+    #[asset(path = "story/main.story.ron")]
+    story: Handle<StoryArc>,
 }
 
 pub struct WorldPlugin;
@@ -65,6 +78,7 @@ impl Plugin for WorldPlugin {
         app.add_plugin(BgPlugin);
         app.add_plugin(GridPlugin);
         app.add_plugin(PlayerPlugin);
+        app.add_plugin(StoryPlugin);
         app.add_plugin(BaseModePlugin);
         app.add_plugin(EventModePlugin);
         app.add_plugin(ExploreModePlugin);
@@ -72,6 +72,7 @@ impl Plugin for BgPlugin {
 pub fn emit_stars(
     time: Res<Time>,
     camera_offset: Res<CameraOffset>,
+    mut streams: ResMut<RngStreams>,
     mut stars: Query<(&mut Star, &mut Visibility, &mut Transform)>,
     mut spawners: Query<(&Children, &mut StarSpawner)>,
 ) {
@@ -85,9 +86,9 @@ pub fn emit_stars(
                             star.lifetime = Timer::from_seconds(spawner.lifetime, false);
                             visibility.is_visible = true;
                             transform.translation = Vec3::new(
-                                (spawner.canvas_size.x * (2.0 * rand::random::<f32>() - 1.0))
+                                (spawner.canvas_size.x * streams.cosmetic.signed())
                                     + camera_offset.value.x,
-                                (spawner.canvas_size.y * (2.0 * rand::random::<f32>() - 1.0))
+                                (spawner.canvas_size.y * streams.cosmetic.signed())
                                     + camera_offset.value.y,
                                 0.0,
                             );
@@ -100,7 +101,7 @@ pub fn emit_stars(
     }
 }
 
-pub fn spawn_stars_spawner(mut commands: Commands) {
+pub fn spawn_stars_spawner(mut commands: Commands, mut streams: ResMut<RngStreams>) {
     let rate = 0.1;
     let spawner = StarSpawner {
         time: Timer::from_seconds(rate, true),
@@ -125,7 +126,7 @@ pub fn spawn_stars_spawner(mut commands: Commands) {
     let mut stars = Vec::new();
     let max_stars = (1.1 * spawner.lifetime / spawner.rate) as usize * spawner.per_burst;
     for _i in 0..max_stars {
-        stars.push(spawn_star(&mut commands, &spawner));
+        stars.push(spawn_star(&mut commands, &mut streams.cosmetic, &spawner));
     }
 
     commands
@@ -136,12 +137,12 @@ pub fn spawn_stars_spawner(mut commands: Commands) {
         .push_children(&stars);
 }
 
-fn spawn_star(commands: &mut Commands, spawner: &StarSpawner) -> Entity {
+fn spawn_star(commands: &mut Commands, rng: &mut Shift64, spawner: &StarSpawner) -> Entity {
     let mut star = SpriteBundle::default();
     star.visibility.is_visible = false;
     star.transform.translation = Vec3::new(
-        spawner.canvas_size.x * (2. * rand::random::<f32>() - 1.),
-        spawner.canvas_size.y * (2. * rand::random::<f32>() - 1.),
+        spawner.canvas_size.x * rng.signed(),
+        spawner.canvas_size.y * rng.signed(),
         0.1,
     );
     star.sprite.color = spawner.color.start;
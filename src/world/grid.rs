@@ -1,28 +1,69 @@
 use super::*;
 
+pub mod coords;
+pub use coords::*;
+
 pub mod data;
 pub use data::*;
 
+pub mod galaxy;
+pub use galaxy::*;
+
 pub mod math;
 pub use math::*;
 
+pub mod narrative;
+pub use narrative::*;
+
 pub mod orient;
 pub use orient::*;
 
+pub mod path;
+pub use path::*;
+
+pub mod registry;
+pub use registry::*;
+
+pub mod sight;
+pub use sight::*;
+
+pub mod spatial;
+pub use spatial::*;
+
+pub mod spawn;
+pub use spawn::*;
+
+pub mod symmetry;
+pub use symmetry::*;
+
+pub mod tables;
+pub use tables::*;
+
 pub mod storage;
 pub use storage::*;
 
 pub mod utilities;
 pub use utilities::*;
 
+pub mod weighted;
+pub use weighted::*;
+
+const PATH_PREVIEW_SIZE: usize = 32;
+
+const GRID_RADIUS: i32 = 38;
+// Note: Used when the galaxy asset does not pick a style,
+// sprites, picking and spawning follow the layout of the grid
+pub const GRID_STYLE: orient::Style = orient::Style::Pointy;
+
 pub struct Grid {
-    pub key: EventKey,
-    pub radius: i32,
-    pub layout: Layout,
-    pub hexmap: HashMap<Axial, HexNode>,
-    pub combat: Option<EventInfo<CombatAction>>,
-    pub energy: Option<EventInfo<EnergyAction>>,
-    pub mining: Option<EventInfo<MiningAction>>,
+    pub hexmap: HexMap<HexNode>,
+    pub fog: HexMap<FogState>,
+    pub visible: HashSet<Axial>,
+    pub sensor: Option<Axial>,
+    pub event: Option<EventInfo<EventPayload>>,
+    pub page: usize,
+    pub story: HashMap<Axial, EventInfo<EventPayload>>,
+    pub region: i32,
 }
 
 #[derive(Component)]
@@ -46,30 +87,17 @@ impl Plugin for GridPlugin {
             app.register_inspectable::<GridMovement>();
         }
 
-        let radius = 38;
-        let layout = Layout {
-            size: Vec2 {
-                x: TILE_SIZE,
-                y: TILE_SIZE,
-            },
-            style: orient::Style::Pointy,
-            origin: Vec2 { x: 0., y: 0. },
-            matrix: Orientation::new(orient::Style::Pointy),
-        };
-        app.insert_resource(Grid {
-            key: EventKey::None,
-            radius: radius,
-            layout: layout,
-            hexmap: HashMap::new(),
-            combat: None,
-            energy: None,
-            mining: None,
-        });
-        GridEvents::load_data(app);
+        app.add_asset::<GalaxyAsset>();
+        app.init_asset_loader::<GalaxyLoader>();
+        app.add_asset::<EventTable>();
+        app.init_asset_loader::<EventTableLoader>();
+
+        app.insert_resource(Grid::new(GRID_STYLE, GRID_RADIUS));
+        app.init_resource::<EventRegistry>();
+        app.insert_resource(JumpPreview::default());
 
-        // FixMe: Execution of systems is not ordered, this is broken.
-        // Verification depends on fixed execution to repeatable values.
-        app.insert_resource(Shift64::new(rand::random::<i64>()));
+        // Note: Reseeded from the run seed once a ship is selected
+        app.insert_resource(RngStreams::new(rand::random::<i64>()));
 
         app.add_system_set(
             SystemSet::on_exit(AppState::GamePlay(GameMode::GameOver)).with_system(exit_state),
@@ -80,15 +108,24 @@ impl Plugin for GridPlugin {
 
         app.add_system_set(SystemSet::on_exit(base_mode).with_system(exit_state));
         app.add_system_set(SystemSet::on_exit(base_mode).with_system(exit_grid_game));
-        // app.add_system_set(SystemSet::on_enter(base_mode).with_system(enter_grid_game));
+        app.add_system_set(SystemSet::on_enter(base_mode).with_system(load_grid_events));
+        app.add_system_set(SystemSet::on_enter(base_mode).with_system(load_grid_style));
 
         app.add_system_set(SystemSet::on_exit(explore_mode).with_system(exit_state));
         app.add_system_set(SystemSet::on_enter(explore_mode).with_system(spawn_explore_movement));
+        app.add_system_set(
+            SystemSet::on_update(explore_mode)
+                .with_system(update_jump_preview.after("player-move")),
+        );
 
         app.add_system_set(SystemSet::on_pause(explore_mode).with_system(pause_explore_movement));
         app.add_system_set(SystemSet::on_resume(explore_mode).with_system(resume_explore_movement));
 
         app.add_system_set(SystemSet::on_enter(explore_mode).with_system(spawn_grid_nodes));
+        app.add_system_set(
+            SystemSet::on_update(explore_mode).with_system(update_fog.after("player-sensor")),
+        );
+        app.add_system_set(SystemSet::on_update(explore_mode).with_system(place_story_events));
         app.add_system_set(SystemSet::on_pause(explore_mode).with_system(pause_grid_nodes));
         app.add_system_set(SystemSet::on_resume(explore_mode).with_system(resume_grid_nodes));
     }
@@ -111,24 +148,146 @@ fn exit_grid_game(mut commands: Commands, query: Query<Entity, With<CleanupGridG
     }
 }
 
-// fn enter_grid_game(mut commands: Commands) {
-//     // TODO: Seed selection gui
-//     log::info!("enter_grid_game");
-// }
+fn load_grid_events(
+    mut registry: ResMut<EventRegistry>,
+    world_assets: Res<WorldAssets>,
+    tables: Res<Assets<EventTable>>,
+) {
+    let handles = [
+        &world_assets.combat_events,
+        &world_assets.energy_events,
+        &world_assets.mining_events,
+    ];
+    registry.load_tables(handles.iter().filter_map(|h| tables.get(h)));
+}
+
+// Note: Runs before the explore grid is entered, nothing is spawned with the old layout
+fn load_grid_style(
+    mut grid: ResMut<Grid>,
+    world_assets: Res<WorldAssets>,
+    galaxy_assets: Res<Assets<GalaxyAsset>>,
+) {
+    let style = galaxy_assets
+        .get(&world_assets.galaxy)
+        .and_then(|galaxy| galaxy.style())
+        .unwrap_or(GRID_STYLE);
+    if grid.style() != style {
+        log::info!("grid style: {:?}", style);
+        grid.set_style(style);
+    }
+}
 
-fn spawn_explore_movement(mut commands: Commands, world_assets: Res<WorldAssets>) {
+fn spawn_explore_movement(
+    mut commands: Commands,
+    grid: Res<Grid>,
+    app_assets: Res<AppAssets>,
+    world_assets: Res<WorldAssets>,
+) {
     log::info!("spawn_explore_movement");
     let mut hex = SpriteBundle {
         texture: world_assets.pointy_hex64_b.clone(),
         ..default()
     };
     hex.visibility.is_visible = true;
+    hex.transform.rotation = grid.hexmap.layout.sprite_rotation();
     hex.transform.translation = Vec3::new(0.0, 0.0, 1.0);
     commands
         .spawn_bundle(hex)
         .insert(Name::new("hex-target"))
         .insert(GridTargetHex)
         .insert(CleanupGrid);
+
+    // Pre spawn the path markers, they are reused for every preview
+    for idx in 0..PATH_PREVIEW_SIZE {
+        let mut marker = SpriteBundle {
+            texture: world_assets.pointy_hex64_b.clone(),
+            ..default()
+        };
+        marker.visibility.is_visible = false;
+        marker.transform.scale = Vec3::splat(0.4);
+        marker.transform.rotation = grid.hexmap.layout.sprite_rotation();
+        marker.transform.translation = Vec3::new(0.0, 0.0, 1.0);
+        commands
+            .spawn_bundle(marker)
+            .insert(Name::new(format!("hex-path-{}", idx)))
+            .insert(GridPathHex)
+            .insert(CleanupGrid);
+    }
+
+    commands
+        .spawn_bundle(Text2dBundle {
+            text: Text::from_section(
+                "",
+                TextStyle {
+                    font: app_assets.gui_font.clone(),
+                    font_size: 32.0,
+                    color: gui::TEXT_BUTTON,
+                },
+            )
+            .with_alignment(TextAlignment::CENTER),
+            transform: Transform::from_xyz(0.0, 0.0, 12.0),
+            ..default()
+        })
+        .insert(Name::new("hex-target-text"))
+        .insert(GridTargetText)
+        .insert(CleanupGrid);
+}
+
+fn update_jump_preview(
+    grid: Res<Grid>,
+    preview: Res<JumpPreview>,
+    target_query: Query<&GridTarget, With<Player>>,
+    mut active_query: Query<
+        (&mut Sprite, &mut Transform),
+        (With<GridTargetHex>, Without<GridPathHex>),
+    >,
+    mut path_query: Query<
+        (&mut Sprite, &mut Visibility, &mut Transform),
+        (With<GridPathHex>, Without<GridTargetHex>),
+    >,
+    mut text_query: Query<
+        (&mut Text, &mut Transform),
+        (
+            With<GridTargetText>,
+            Without<GridTargetHex>,
+            Without<GridPathHex>,
+        ),
+    >,
+) {
+    let move_to = target_query.single();
+    let color = match preview.check {
+        JumpCheck::Ready => Color::rgb(1., 1., 1.),
+        _ => Color::rgb(1., 0., 0.),
+    };
+
+    let (mut active_sprite, mut active_transform) = active_query.single_mut();
+    active_sprite.color = color;
+    active_transform.translation.x = move_to.mouse.x;
+    active_transform.translation.y = move_to.mouse.y;
+
+    // Note: Jump landings are drawn brighter than the hexes passed
+    let mut route = preview.route.iter();
+    for (mut sprite, mut visibility, mut transform) in path_query.iter_mut() {
+        if let Some(hex) = route.next() {
+            let pos = grid.hexmap.layout.center_for(hex);
+            let landing = preview.jumps.iter().any(|jump| jump.hex == *hex);
+            sprite.color = match landing {
+                true => color,
+                false => color * 0.6,
+            };
+            transform.translation.x = pos.x;
+            transform.translation.y = pos.y;
+            visibility.is_visible = true;
+        } else {
+            visibility.is_visible = false;
+        }
+    }
+
+    let (mut text, mut transform) = text_query.single_mut();
+    text.sections[0].value = preview.label();
+    text.sections[0].style.color = color;
+    transform.translation.x = move_to.mouse.x;
+    transform.translation.y = move_to.mouse.y + TILE_SIZE * 1.2;
 }
 
 fn pause_explore_movement(mut grid_root: Query<&mut Visibility, With<GridRoot>>) {
@@ -146,184 +305,139 @@ fn resume_explore_movement(mut grid_root: Query<&mut Visibility, With<GridRoot>>
 fn spawn_grid_nodes(
     mut commands: Commands,
     mut grid: ResMut<Grid>,
-    mut rng: ResMut<Shift64>,
+    mut streams: ResMut<RngStreams>,
     world_assets: Res<WorldAssets>,
+    galaxy_assets: Res<Assets<GalaxyAsset>>,
 ) {
+    // Note: A broken galaxy file never gets past the loading state,
+    // the built in shape only stands in when the asset is missing
+    let rng = &mut streams.map;
+    let seed = rng.shift();
+    let galaxy = match galaxy_assets.get(&world_assets.galaxy) {
+        Some(asset) => asset.build(seed),
+        None => GalaxyLayout::default(),
+    };
+
     // Main Grid nodes
-    let mut root = HexMap::new(
-        grid.layout.size,
-        grid.layout.style,
-        grid.layout.origin,
-        grid.radius,
-    );
+    let mut root = HexMap::with_layout(grid.hexmap.layout.clone(), galaxy.radius);
+    root.fill_with(|_| HexNode::default());
     _spawn_grid_node(
         &mut root,
         &mut commands,
         &world_assets,
-        false,
+        0.,
         rng.shift(),
-        Color::rgba(0.6, 0.4, 0.6, 0.3),
+        galaxy.color(),
     );
 
-    // Sub grid nodes
-    let offset = 24;
-    let mut node_a = HexMap::new(
-        Vec2 {
-            x: TILE_SIZE,
-            y: TILE_SIZE,
-        },
-        orient::Style::Pointy,
-        grid.layout.center_for(&Axial { q: -offset, r: 0 }),
-        12,
-    );
-    _spawn_grid_node(
-        &mut node_a,
-        &mut commands,
-        &world_assets,
-        true,
-        rng.shift(),
-        Color::rgba(0.8, 0.6, 0.8, 0.2),
-    );
-    root.collect(&mut node_a);
-
-    let mut node_b = HexMap::new(
-        Vec2 {
-            x: TILE_SIZE,
-            y: TILE_SIZE,
-        },
-        orient::Style::Pointy,
-        grid.layout.center_for(&Axial { q: 0, r: -offset }),
-        12,
-    );
-    _spawn_grid_node(
-        &mut node_b,
-        &mut commands,
-        &world_assets,
-        true,
-        rng.shift(),
-        Color::rgba(0.8, 0.6, 0.8, 0.2),
-    );
-    root.collect(&mut node_b);
-
-    let mut node_c = HexMap::new(
-        Vec2 {
-            x: TILE_SIZE,
-            y: TILE_SIZE,
-        },
-        orient::Style::Pointy,
-        grid.layout.center_for(&Axial {
-            q: -offset,
-            r: offset,
-        }),
-        12,
-    );
-    _spawn_grid_node(
-        &mut node_c,
-        &mut commands,
-        &world_assets,
-        true,
-        rng.shift(),
-        Color::rgba(0.8, 0.6, 0.8, 0.2),
-    );
-    root.collect(&mut node_c);
-
-    let mut node_d = HexMap::new(
-        Vec2 {
-            x: TILE_SIZE,
-            y: TILE_SIZE,
-        },
-        orient::Style::Pointy,
-        Vec2 { x: 0., y: 0. },
-        12,
-    );
-    _spawn_grid_node(
-        &mut node_d,
-        &mut commands,
-        &world_assets,
-        true,
-        rng.shift(),
-        Color::rgba(0.8, 0.6, 0.8, 0.2),
-    );
-    root.collect(&mut node_d);
+    // Sub grid nodes, one per cluster of the galaxy layout
+    for cluster in galaxy.clusters.iter() {
+        let mut node = HexMap::with_layout(grid.hexmap.layout.clone(), cluster.radius);
+        node.layout.origin = grid.hexmap.layout.center_for(&cluster.center());
+        node.fill_with(|_| HexNode::default());
+        _spawn_grid_node(
+            &mut node,
+            &mut commands,
+            &world_assets,
+            cluster.density,
+            rng.shift(),
+            cluster.color(),
+        );
+        root.merge(node);
+    }
 
-    let mut node_e = HexMap::new(
-        Vec2 {
-            x: TILE_SIZE,
-            y: TILE_SIZE,
-        },
-        orient::Style::Pointy,
-        grid.layout.center_for(&Axial { q: offset, r: 0 }),
-        12,
-    );
-    _spawn_grid_node(
-        &mut node_e,
-        &mut commands,
-        &world_assets,
-        true,
-        rng.shift(),
-        Color::rgba(0.8, 0.6, 0.8, 0.2),
-    );
-    root.collect(&mut node_e);
+    grid.hexmap = root;
+    grid.region = galaxy.radius;
+    grid.story.clear();
+    grid.reset_fog();
+}
 
-    let mut node_f = HexMap::new(
-        Vec2 {
-            x: TILE_SIZE,
-            y: TILE_SIZE,
+fn place_story_events(
+    mut commands: Commands,
+    mut grid: ResMut<Grid>,
+    mut story: ResMut<StoryState>,
+    arcs: Res<Assets<StoryArc>>,
+    world_assets: Res<WorldAssets>,
+    root_query: Query<Entity, With<GridRoot>>,
+    player_query: Query<(&HealthRecource, &EnergyRecource, &CargoRecource), With<Player>>,
+) {
+    let chapter = match arcs.get(&world_assets.story) {
+        Some(arc) => match arc.chapters.get(story.chapter) {
+            Some(chapter) => chapter,
+            None => return,
         },
-        orient::Style::Pointy,
-        grid.layout.center_for(&Axial { q: 0, r: offset }),
-        12,
-    );
-    _spawn_grid_node(
-        &mut node_f,
-        &mut commands,
-        &world_assets,
-        true,
-        rng.shift(),
-        Color::rgba(0.8, 0.6, 0.8, 0.2),
-    );
-    root.collect(&mut node_f);
+        None => return,
+    };
+    let root = match root_query.iter().next() {
+        Some(root) => root,
+        None => return,
+    };
 
-    let mut node_g = HexMap::new(
-        Vec2 {
-            x: TILE_SIZE,
-            y: TILE_SIZE,
-        },
-        orient::Style::Pointy,
-        grid.layout.center_for(&Axial {
-            q: offset,
-            r: -offset,
-        }),
-        12,
-    );
-    _spawn_grid_node(
-        &mut node_g,
-        &mut commands,
-        &world_assets,
-        true,
-        rng.shift(),
-        Color::rgba(0.8, 0.6, 0.8, 0.2),
-    );
-    root.collect(&mut node_g);
+    let (health, energy, cargo) = player_query.single();
+    let stats = PlayerStats::new(health, energy, cargo);
+    for event in chapter.events.iter() {
+        if story.placed.contains(&event.id) {
+            continue;
+        }
+        if !event.requires.iter().all(|c| c.check(&stats, &story)) {
+            continue;
+        }
+        story.placed.insert(event.id.clone());
 
-    grid.hexmap = root.nodes;
+        let hex = event.hex();
+        if !grid.on_grid(&hex) {
+            log::warn!("place_story_events: {} is off grid at {}", event.id, hex);
+            continue;
+        }
+        log::info!("place_story_events: {} at {}", event.id, hex);
+
+        let mut marker = TextureAtlasSprite::new(31);
+        marker.color = Color::rgb(1.0, 0.85, 0.4);
+        let tint = HexTint(marker.color);
+        marker.custom_size = Some(Vec2::splat(TILE_SIZE * 0.6));
+        let pos = grid.hexmap.layout.center_for(&hex);
+        let entity = commands
+            .spawn_bundle(SpriteSheetBundle {
+                sprite: marker,
+                texture_atlas: world_assets.base_space_sheet.clone(),
+                transform: Transform {
+                    translation: Vec3::new(pos.x, pos.y, 9.0),
+                    ..Default::default()
+                },
+                ..Default::default()
+            })
+            .insert(Name::new(format!("story-{}", event.id)))
+            .insert(tint)
+            .id();
+        commands.entity(root).add_child(entity);
+
+        // Note: The story event replaces whatever was rolled for the hex
+        if let Some(old) = grid.clr_node(&hex) {
+            commands.entity(old).despawn_recursive();
+        }
+        if let Some(node) = grid.hexmap.get_mut(&hex) {
+            node.key = EventKey::Story;
+            node.entity = Some(entity);
+        }
+        grid.story.insert(hex, event.event.info());
+    }
 }
 
 fn _spawn_grid_node(
-    node: &mut HexMap,
+    node: &mut HexMap<HexNode>,
     commands: &mut Commands,
     world_assets: &Res<WorldAssets>,
-    run: bool,
+    density: f32,
     seed: i64,
     color: Color,
 ) {
     // Setup the node entity and spawn the grid
     let name = format!("node-{}:{}", 0, 0);
     let node_id = commands.spawn().insert(Name::new(name)).id();
-    let list = node.spawn_entities(color, node_id, commands, &world_assets);
+    let list = spawn_hex_entities(node, color, node_id, commands, &world_assets);
 
-    if run {
-        node.spawn_points(seed, commands, world_assets);
+    if density > 0. {
+        spawn_hex_points(node, seed, density, commands, world_assets);
     }
 
     // Finalize hex node and entities as children
@@ -337,25 +451,129 @@ fn _spawn_grid_node(
         .push_children(&list);
 }
 
-fn pause_grid_nodes(mut nodes_query: Query<&mut Visibility, With<GridTargetHex>>) {
+fn update_fog(
+    grid: Res<Grid>,
+    mut tile_query: Query<(&HexTint, &Transform, &mut Sprite)>,
+    mut point_query: Query<(&HexTint, &mut Visibility, &mut TextureAtlasSprite)>,
+) {
+    if !grid.is_changed() {
+        return;
+    }
+
+    // Hex tiles are dimmed until they have been seen by the sensors
+    for (tint, transform, mut sprite) in tile_query.iter_mut() {
+        let hex = grid.hexmap.layout.hex_for(Vec2 {
+            x: transform.translation.x,
+            y: transform.translation.y,
+        });
+        let alpha = match grid.get_fog(&hex) {
+            _ if grid.visible.contains(&hex) => 1.0,
+            FogState::Unexplored => 0.25,
+            FogState::Scanned => 0.6,
+            FogState::Visited => 0.8,
+        };
+        sprite.color = tint.0;
+        sprite.color.set_a(tint.0.a() * alpha);
+    }
+
+    // Points of interest stay hidden until scanned or seen,
+    // out of sight they keep their own tint only darker
+    for (hex, node) in grid.hexmap.iter() {
+        let entity = match node.entity {
+            Some(entity) => entity,
+            None => continue,
+        };
+        if let Ok((tint, mut visibility, mut sprite)) = point_query.get_mut(entity) {
+            visibility.is_visible = grid.get_fog(hex) != FogState::Unexplored;
+            let dim = match grid.visible.contains(hex) {
+                true => 1.0,
+                false => 0.6,
+            };
+            sprite.color = Color::rgba(
+                tint.0.r() * dim,
+                tint.0.g() * dim,
+                tint.0.b() * dim,
+                tint.0.a(),
+            );
+        }
+    }
+}
+
+fn pause_grid_nodes(
+    mut nodes_query: Query<
+        &mut Visibility,
+        Or<(With<GridTargetHex>, With<GridTargetText>, With<GridPathHex>)>,
+    >,
+) {
     for mut visibility in nodes_query.iter_mut() {
         visibility.is_visible = false;
     }
 }
 
-fn resume_grid_nodes(mut nodes_query: Query<&mut Visibility, With<GridTargetHex>>) {
+fn resume_grid_nodes(
+    mut nodes_query: Query<&mut Visibility, Or<(With<GridTargetHex>, With<GridTargetText>)>>,
+) {
     for mut visibility in nodes_query.iter_mut() {
         visibility.is_visible = true;
     }
 }
 
 impl Grid {
+    pub fn new(style: orient::Style, radius: i32) -> Self {
+        let layout = Layout::new(
+            Vec2 {
+                x: TILE_SIZE,
+                y: TILE_SIZE,
+            },
+            style,
+            Vec2 { x: 0., y: 0. },
+        );
+        Grid {
+            hexmap: HexMap::with_layout(layout.clone(), radius),
+            fog: HexMap::with_layout(layout, radius),
+            visible: HashSet::new(),
+            sensor: None,
+            event: None,
+            page: 0,
+            story: HashMap::new(),
+            region: radius,
+        }
+    }
+
+    pub fn style(&self) -> orient::Style {
+        self.hexmap.layout.style
+    }
+
+    // Note: Only the layouts change, the nodes are spawned again on entering the explore grid
+    pub fn set_style(&mut self, style: orient::Style) {
+        let layout = &self.hexmap.layout;
+        let layout = Layout::new(layout.size, style, layout.origin);
+        self.fog.layout = layout.clone();
+        self.hexmap.layout = layout;
+    }
+
     pub fn on_grid(&self, hex: &Axial) -> bool {
-        self.radius >= hex.distance(&Axial { q: 0, r: 0 })
+        self.hexmap.radius >= hex.distance(&Axial { q: 0, r: 0 })
+    }
+
+    // Note: The story opens the galaxy up chapter by chapter
+    pub fn in_region(&self, hex: &Axial) -> bool {
+        self.region >= hex.distance(&Axial { q: 0, r: 0 })
     }
 
     pub fn get_hex(&mut self, position: Vec2) -> Axial {
-        self.layout.hex_for(position)
+        self.hexmap.layout.hex_for(position)
+    }
+
+    pub fn hex_cost(&self, hex: &Axial) -> Option<i32> {
+        match self.on_grid(hex) && self.in_region(hex) {
+            true => Some(1),
+            false => None,
+        }
+    }
+
+    pub fn find_path(&self, from: &Axial, to: &Axial, limit: i32) -> Option<HexPath> {
+        HexPath::find(from, to, limit, |hex| self.hex_cost(hex))
     }
 
     // pub fn get_value(&mut self, hex: Axial) -> i32 {
@@ -372,99 +590,116 @@ impl Grid {
     //     None
     // }
 
-    pub fn get_event_key(&mut self, hex: Axial) -> EventKey {
-        if let Some(node) = self.hexmap.get(&hex) {
-            return node.key;
+    pub fn blocks_sight(&self, hex: &Axial) -> bool {
+        match self.hexmap.get(hex) {
+            Some(node) => node.blocks_sight(),
+            None => false,
         }
-        EventKey::Combat
     }
 
-    pub fn get_event_data(&self) -> EventData {
-        match self.key {
-            EventKey::None => EventData::default(),
-            EventKey::Combat => {
-                if let Some(event) = &self.combat {
-                    return event.data.clone();
-                }
-                EventData::default()
-            }
-            EventKey::Energy => {
-                if let Some(event) = &self.energy {
-                    return event.data.clone();
+    pub fn has_line_of_sight(&self, a: &Axial, b: &Axial) -> bool {
+        has_line_of_sight(a, b, |hex| self.blocks_sight(hex))
+    }
+
+    pub fn field_of_view(&self, center: &Axial, radius: i32) -> HashSet<Axial> {
+        let mut result = field_of_view(center, radius, |hex| self.blocks_sight(hex));
+        result.retain(|hex| self.on_grid(hex));
+        result
+    }
+
+    pub fn reset_fog(&mut self) {
+        self.fog = HexMap::with_layout(self.hexmap.layout.clone(), self.hexmap.radius);
+        self.fog.fill_with(|_| FogState::Unexplored);
+        self.visible.clear();
+        self.sensor = None;
+    }
+
+    pub fn get_fog(&self, hex: &Axial) -> FogState {
+        match self.fog.get(hex) {
+            Some(state) => *state,
+            None => FogState::Unexplored,
+        }
+    }
+
+    // Update the sensor view, everything seen once counts as scanned
+    pub fn update_sensors(&mut self, center: &Axial, radius: i32) {
+        self.visible = self.field_of_view(center, radius);
+        self.sensor = Some(*center);
+        for hex in self.visible.iter() {
+            if let Some(state) = self.fog.get_mut(hex) {
+                if *state == FogState::Unexplored {
+                    *state = FogState::Scanned;
                 }
-                EventData::default()
             }
-            EventKey::Mining => {
-                if let Some(event) = &self.mining {
-                    return event.data.clone();
+        }
+    }
+
+    // Long range scan, reveals everything in range ignoring line of sight
+    pub fn scan(&mut self, center: &Axial, radius: i32) {
+        for hex in center.range(radius) {
+            if let Some(state) = self.fog.get_mut(&hex) {
+                if *state == FogState::Unexplored {
+                    *state = FogState::Scanned;
                 }
-                EventData::default()
             }
         }
     }
 
-    pub fn clr_node(&mut self, hex: &Axial) -> Option<Entity> {
-        if let Some(node) = self.hexmap.get_mut(hex) {
-            let entity = node.entity.clone();
-            node.key = EventKey::Combat;
-            node.value = 0;
-            node.entity = None;
-            return entity;
+    pub fn visit(&mut self, hex: &Axial) {
+        if let Some(state) = self.fog.get_mut(hex) {
+            *state = FogState::Visited;
         }
-        None
     }
 
-    // FixMe: Rust must have cleaner way to do this setup
-    pub fn clr_event(&mut self) {
-        log::info!("Set Event Clear");
-        self.key = EventKey::None;
-        self.combat = None;
-        self.energy = None;
-        self.mining = None;
+    pub fn get_event_key(&mut self, hex: Axial) -> EventKey {
+        if let Some(node) = self.hexmap.get(&hex) {
+            return node.key;
+        }
+        EventKey::Combat
     }
 
-    pub fn get_event_combat(&mut self) -> CombatAction {
-        if let Some(event) = &self.combat {
-            return event.action;
+    pub fn active_key(&self) -> EventKey {
+        match &self.event {
+            Some(event) => event.data.key,
+            None => EventKey::None,
         }
-        CombatAction::default()
     }
 
-    pub fn get_event_energy(&mut self) -> EnergyAction {
-        if let Some(event) = &self.energy {
-            return event.action;
+    pub fn get_event_data(&self) -> EventData {
+        match &self.event {
+            Some(event) => event.data.clone(),
+            None => EventData::default(),
         }
-        EnergyAction::default()
     }
 
-    pub fn get_event_mining(&mut self) -> MiningAction {
-        if let Some(event) = &self.mining {
-            return event.action;
+    pub fn get_event_action(&self) -> EventPayload {
+        match &self.event {
+            Some(event) => event.action,
+            None => EventPayload::None,
         }
-        MiningAction::default()
     }
 
-    pub fn set_event_combat(&mut self, event: EventInfo<CombatAction>) {
-        log::info!("Set Event Combat: {}", event.data.title);
-        self.key = EventKey::Combat;
-        self.combat = Some(event);
-        self.energy = None;
-        self.mining = None;
+    pub fn clr_node(&mut self, hex: &Axial) -> Option<Entity> {
+        if let Some(node) = self.hexmap.get_mut(hex) {
+            let entity = node.entity.clone();
+            node.key = EventKey::Combat;
+            node.value = 0;
+            node.entity = None;
+            self.story.remove(hex);
+            return entity;
+        }
+        None
     }
 
-    pub fn set_event_energy(&mut self, event: EventInfo<EnergyAction>) {
-        log::info!("Set Event Energy: {}", event.data.title);
-        self.key = EventKey::Energy;
-        self.combat = None;
-        self.energy = Some(event);
-        self.mining = None;
+    pub fn set_event(&mut self, event: EventInfo<EventPayload>) {
+        log::info!("Set Event {:?}: {}", event.data.key, event.data.title);
+        self.event = Some(event);
+        self.page = 0;
     }
 
-    pub fn set_event_mining(&mut self, event: EventInfo<MiningAction>) {
-        log::info!("Set Event Mining: {}", event.data.title);
-        self.key = EventKey::Mining;
-        self.combat = None;
-        self.energy = None;
-        self.mining = Some(event);
+    pub fn clr_event(&mut self) {
+        log::info!("Set Event Clear");
+        self.event = None;
+        self.page = 0;
     }
 }
@@ -0,0 +1,191 @@
+use super::*;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum OffsetKind {
+    OddR,  // Pointy, odd rows shoved right
+    EvenR, // Pointy, even rows shoved right
+    OddQ,  // Flat, odd columns shoved down
+    EvenQ, // Flat, even columns shoved down
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DoubledKind {
+    Width,  // Pointy, columns step by two
+    Height, // Flat, rows step by two
+}
+
+#[derive(Clone, Copy, Debug, Default, Hash, PartialEq, Eq)]
+pub struct Offset {
+    pub col: i32,
+    pub row: i32,
+}
+
+#[derive(Clone, Copy, Debug, Default, Hash, PartialEq, Eq)]
+pub struct Doubled {
+    pub col: i32,
+    pub row: i32,
+}
+
+impl From<Axial> for Cuboid {
+    fn from(v: Axial) -> Self {
+        v.cuboid()
+    }
+}
+
+impl From<Cuboid> for Axial {
+    fn from(v: Cuboid) -> Self {
+        Self { q: v.q, r: v.r }
+    }
+}
+
+impl Axial {
+    pub fn to_offset(&self, kind: OffsetKind) -> Offset {
+        let (q, r) = (self.q, self.r);
+        match kind {
+            OffsetKind::OddR => Offset {
+                col: q + (r - (r & 1)) / 2,
+                row: r,
+            },
+            OffsetKind::EvenR => Offset {
+                col: q + (r + (r & 1)) / 2,
+                row: r,
+            },
+            OffsetKind::OddQ => Offset {
+                col: q,
+                row: r + (q - (q & 1)) / 2,
+            },
+            OffsetKind::EvenQ => Offset {
+                col: q,
+                row: r + (q + (q & 1)) / 2,
+            },
+        }
+    }
+
+    pub fn to_doubled(&self, kind: DoubledKind) -> Doubled {
+        match kind {
+            DoubledKind::Width => Doubled {
+                col: 2 * self.q + self.r,
+                row: self.r,
+            },
+            DoubledKind::Height => Doubled {
+                col: self.q,
+                row: 2 * self.r + self.q,
+            },
+        }
+    }
+}
+
+impl Offset {
+    pub fn to_axial(&self, kind: OffsetKind) -> Axial {
+        let (col, row) = (self.col, self.row);
+        match kind {
+            OffsetKind::OddR => Axial {
+                q: col - (row - (row & 1)) / 2,
+                r: row,
+            },
+            OffsetKind::EvenR => Axial {
+                q: col - (row + (row & 1)) / 2,
+                r: row,
+            },
+            OffsetKind::OddQ => Axial {
+                q: col,
+                r: row - (col - (col & 1)) / 2,
+            },
+            OffsetKind::EvenQ => Axial {
+                q: col,
+                r: row - (col + (col & 1)) / 2,
+            },
+        }
+    }
+}
+
+impl Doubled {
+    // Note: Only cells where col + row is even are valid hexes
+    pub fn to_axial(&self, kind: DoubledKind) -> Axial {
+        match kind {
+            DoubledKind::Width => Axial {
+                q: (self.col - self.row) / 2,
+                r: self.row,
+            },
+            DoubledKind::Height => Axial {
+                q: self.col,
+                r: (self.row - self.col) / 2,
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Axial, Cuboid, Doubled, DoubledKind, Offset, OffsetKind};
+
+    const KINDS: [OffsetKind; 4] = [
+        OffsetKind::OddR,
+        OffsetKind::EvenR,
+        OffsetKind::OddQ,
+        OffsetKind::EvenQ,
+    ];
+
+    #[test]
+    fn convert_cuboid() {
+        for hex in (Axial { q: 0, r: 0 }).range(6) {
+            let cube: Cuboid = hex.into();
+            assert_eq!(cube.q + cube.r + cube.s, 0);
+            assert_eq!(Axial::from(cube), hex);
+        }
+    }
+
+    #[test]
+    fn convert_offset() {
+        for kind in KINDS {
+            for hex in (Axial { q: 0, r: 0 }).range(6) {
+                assert_eq!(hex.to_offset(kind).to_axial(kind), hex);
+            }
+        }
+
+        // Note: Reference values for the hex (1, -3)
+        let hex = Axial { q: 1, r: -3 };
+        assert_eq!(hex.to_offset(OffsetKind::OddR), Offset { col: -1, row: -3 });
+        assert_eq!(hex.to_offset(OffsetKind::EvenR), Offset { col: 0, row: -3 });
+        assert_eq!(hex.to_offset(OffsetKind::OddQ), Offset { col: 1, row: -3 });
+        assert_eq!(hex.to_offset(OffsetKind::EvenQ), Offset { col: 1, row: -2 });
+    }
+
+    #[test]
+    fn convert_offset_rows() {
+        // Every cell of a rectangle maps to a unique hex and back
+        for kind in KINDS {
+            let mut seen = Vec::new();
+            for row in -4..5 {
+                for col in -4..5 {
+                    let cell = Offset { col, row };
+                    let hex = cell.to_axial(kind);
+                    assert_eq!(hex.to_offset(kind), cell);
+                    assert!(!seen.contains(&hex));
+                    seen.push(hex);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn convert_doubled() {
+        for kind in [DoubledKind::Width, DoubledKind::Height] {
+            for hex in (Axial { q: 0, r: 0 }).range(6) {
+                let cell = hex.to_doubled(kind);
+                assert_eq!((cell.col + cell.row) % 2, 0);
+                assert_eq!(cell.to_axial(kind), hex);
+            }
+        }
+
+        let hex = Axial { q: 1, r: -3 };
+        assert_eq!(
+            hex.to_doubled(DoubledKind::Width),
+            Doubled { col: -1, row: -3 }
+        );
+        assert_eq!(
+            hex.to_doubled(DoubledKind::Height),
+            Doubled { col: 1, row: -5 }
+        );
+    }
+}
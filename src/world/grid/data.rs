@@ -1,18 +1,30 @@
+use serde::Deserialize;
+
 use super::*;
 
-#[derive(Default, Clone, Copy, PartialEq, Eq)]
+#[derive(Default, Clone, Copy, Debug, Hash, PartialEq, Eq, Deserialize)]
 pub enum EventKey {
     #[default]
     None,
     Combat,
     Energy,
     Mining,
+    Story,
 }
 
-pub struct GridEvents {
-    pub combat: Vec<EventInfo<CombatAction>>,
-    pub energy: Vec<EventInfo<EnergyAction>>,
-    pub mining: Vec<EventInfo<MiningAction>>,
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum FogState {
+    #[default]
+    Unexplored,
+    Scanned,
+    Visited,
+}
+
+#[derive(Clone, Copy)]
+pub struct HexNode {
+    pub key: EventKey,
+    pub value: i32,
+    pub entity: Option<Entity>,
 }
 
 #[derive(Default, Clone)]
@@ -27,6 +39,7 @@ pub struct EventData {
     pub enter: bool,
     pub title: String,
     pub descr: String,
+    pub pages: Vec<EventPage>,
 }
 
 #[derive(Default, Clone)]
@@ -35,192 +48,59 @@ pub struct EventAction {
     pub leave: String,
 }
 
-#[derive(Default, Clone, Copy)]
+#[derive(Default, Clone, Copy, Debug, PartialEq, Eq, Deserialize)]
 pub struct CombatAction {
     pub is_large: bool,
     pub enemies: u16,
+    #[serde(default)]
+    pub mix: CombatMix,
 }
 
-#[derive(Default, Clone, Copy)]
+// Named spread of the behaviours the ships of a fight pick from
+#[derive(Default, Clone, Copy, Debug, PartialEq, Eq, Deserialize)]
+pub enum CombatMix {
+    #[default]
+    Balanced,
+    Hunters,
+    Swarm,
+    Skirmish,
+}
+
+#[derive(Default, Clone, Copy, Debug, PartialEq, Eq, Deserialize)]
 pub struct EnergyAction {
     pub is_large: bool,
     pub energy: u16,
 }
 
-#[derive(Default, Clone, Copy)]
+#[derive(Default, Clone, Copy, Debug, PartialEq, Eq, Deserialize)]
 pub struct MiningAction {
     pub is_large: bool,
     pub material: u16,
 }
 
-impl GridEvents {
-    pub fn load_data(app: &mut App) {
-        let mut event_data = GridEvents {
-            combat: Vec::new(),
-            energy: Vec::new(),
-            mining: Vec::new(),
-        };
-
-        event_data.combat.push(EventInfo {
-            data: EventData {
-                key: EventKey::Combat,
-                enter: false,
-                title: "Empty Space".to_string(),
-                descr: "An empty section of space.".to_string(),
-            },
-            action: CombatAction {
-                is_large: false,
-                enemies: 0,
-            },
-        });
-        event_data.combat.push(EventInfo {
-            data: EventData {
-                key: EventKey::Combat,
-                enter: true,
-                title: "Small Amboush".to_string(),
-                descr: "A small amboush.".to_string(),
-            },
-            action: CombatAction {
-                is_large: false,
-                enemies: 4,
-            },
-        });
-        event_data.combat.push(EventInfo {
-            data: EventData {
-                key: EventKey::Combat,
-                enter: true,
-                title: "Big Amboush".to_string(),
-                descr: "A big amboush.".to_string(),
-            },
-            action: CombatAction {
-                is_large: true,
-                enemies: 8,
-            },
-        });
-
-        event_data.energy.push(EventInfo {
-            data: EventData {
-                key: EventKey::Energy,
-                enter: false,
-                title: "Dimm Star".to_string(),
-                descr: "This star bearly puts out light.".to_string(),
-            },
-            action: EnergyAction {
-                is_large: false,
-                energy: 10,
-            },
-        });
-        event_data.energy.push(EventInfo {
-            data: EventData {
-                key: EventKey::Energy,
-                enter: false,
-                title: "Small Star".to_string(),
-                descr: "A small star.".to_string(),
-            },
-            action: EnergyAction {
-                is_large: false,
-                energy: 30,
-            },
-        });
-        event_data.energy.push(EventInfo {
-            data: EventData {
-                key: EventKey::Energy,
-                enter: false,
-                title: "Big Star".to_string(),
-                descr: "A big star.".to_string(),
-            },
-            action: EnergyAction {
-                is_large: true,
-                energy: 60,
-            },
-        });
-
-        event_data.mining.push(EventInfo {
-            data: EventData {
-                key: EventKey::Mining,
-                enter: false,
-                title: "Empty Belt".to_string(),
-                descr: "Seems there is nothing but dust left.".to_string(),
-            },
-            action: MiningAction {
-                is_large: false,
-                material: 1,
-            },
-        });
-        event_data.mining.push(EventInfo {
-            data: EventData {
-                key: EventKey::Mining,
-                enter: false,
-                title: "Small Belt".to_string(),
-                descr: "A small astroid belt.".to_string(),
-            },
-            action: MiningAction {
-                is_large: false,
-                material: 5,
-            },
-        });
-        event_data.mining.push(EventInfo {
-            data: EventData {
-                key: EventKey::Mining,
-                enter: false,
-                title: "Big Belt".to_string(),
-                descr: "A big astroid belt.".to_string(),
-            },
-            action: MiningAction {
-                is_large: true,
-                material: 10,
-            },
-        });
-
-        app.insert_resource(event_data);
-    }
-
-    pub fn get_actions(key: EventKey) -> EventAction {
-        match key {
-            EventKey::None => EventAction {
-                enter: "enter".to_string(),
-                leave: "leave".to_string(),
-            },
-            EventKey::Combat => EventAction {
-                enter: "attack".to_string(),
-                leave: "escape".to_string(),
-            },
-            EventKey::Energy => EventAction {
-                enter: "--".to_string(),
-                leave: "harvest".to_string(),
-            },
-            EventKey::Mining => EventAction {
-                enter: "--".to_string(),
-                leave: "gather".to_string(),
-            },
-        }
-    }
-
-    pub fn roll_combat_table(&self, seed: i64) -> EventInfo<CombatAction> {
-        let mut rng = Shift64::new(seed);
-        if rng.i32(256) > 92 {
-            let idx = rng.usize(self.combat.len()) + 1;
-            return self.combat[idx].clone();
+impl EventAction {
+    pub fn new(enter: &str, leave: &str) -> Self {
+        Self {
+            enter: enter.to_string(),
+            leave: leave.to_string(),
         }
-        self.combat[0].clone()
     }
+}
 
-    pub fn roll_energy_table(&self, seed: i64) -> EventInfo<EnergyAction> {
-        let mut rng = Shift64::new(seed);
-        if rng.i32(256) > 48 {
-            let idx = rng.usize(self.energy.len() - 1) + 1;
-            return self.energy[idx].clone();
+impl Default for HexNode {
+    fn default() -> Self {
+        Self {
+            key: EventKey::Combat,
+            value: 0,
+            entity: None,
         }
-        self.energy[0].clone()
     }
+}
 
-    pub fn roll_mining_table(&self, seed: i64) -> EventInfo<MiningAction> {
-        let mut rng = Shift64::new(seed);
-        if rng.i32(256) > 24 {
-            let idx = rng.usize(self.mining.len() - 1) + 1;
-            return self.mining[idx].clone();
-        }
-        self.mining[0].clone()
+impl HexNode {
+    // Note: Astroid belts block sensors and line of sight
+    pub fn blocks_sight(&self) -> bool {
+        self.key == EventKey::Mining
     }
 }
 
@@ -231,6 +111,7 @@ impl EventData {
             enter: false,
             title: "Nothing".to_string(),
             descr: "Nothing to see, nothing to do here.".to_string(),
+            pages: Vec::new(),
         }
     }
 }
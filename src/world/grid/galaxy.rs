@@ -0,0 +1,336 @@
+use bevy::asset::{AssetLoader, LoadContext, LoadedAsset};
+use bevy::reflect::TypeUuid;
+use bevy::utils::BoxedFuture;
+use serde::Deserialize;
+
+use super::*;
+
+const MAX_DENSITY: f32 = 4.0;
+
+// A cluster is a sub grid of the galaxy with its own points of interest
+#[derive(Clone, Debug, Deserialize)]
+pub struct ClusterLayout {
+    pub center: (i32, i32),
+    pub radius: i32,
+    pub color: [f32; 4],
+    pub density: f32,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct GalaxyLayout {
+    pub radius: i32,
+    pub color: [f32; 4],
+    pub clusters: Vec<ClusterLayout>,
+    #[serde(default)]
+    pub style: Option<orient::Style>, // Hex orientation, the grid default if not set
+}
+
+// Rules to roll a new layout from the run seed, every range is inclusive
+#[derive(Clone, Debug, Deserialize)]
+pub struct GalaxyRules {
+    pub radius: i32,
+    pub color: [f32; 4],
+    pub core: (i32, i32),
+    pub clusters: (i32, i32),
+    pub cluster_radius: (i32, i32),
+    pub spacing: (i32, i32),
+    pub density: (f32, f32),
+    pub palette: Vec<[f32; 4]>,
+    #[serde(default)]
+    pub style: Option<orient::Style>,
+}
+
+#[derive(Clone, Debug, Deserialize, TypeUuid)]
+#[uuid = "7c4b8e0e-2f4a-4d4b-9a57-5b3c51f0a8d2"]
+pub enum GalaxyAsset {
+    Fixed(GalaxyLayout),
+    Random(GalaxyRules),
+}
+
+#[derive(Default)]
+pub struct GalaxyLoader;
+
+impl AssetLoader for GalaxyLoader {
+    fn load<'a>(
+        &'a self,
+        bytes: &'a [u8],
+        load_context: &'a mut LoadContext,
+    ) -> BoxedFuture<'a, Result<(), anyhow::Error>> {
+        Box::pin(async move {
+            let path = load_context.path().display().to_string();
+            let asset = GalaxyAsset::from_bytes(&path, bytes).map_err(|e| anyhow::anyhow!(e))?;
+
+            load_context.set_default_asset(LoadedAsset::new(asset));
+            Ok(())
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["galaxy.ron"]
+    }
+}
+
+impl GalaxyAsset {
+    // Parse and check a galaxy, errors start with the path of the file
+    pub fn from_bytes(path: &str, bytes: &[u8]) -> Result<GalaxyAsset, String> {
+        let asset =
+            ron::de::from_bytes::<GalaxyAsset>(bytes).map_err(|e| format!("{}: {}", path, e))?;
+        asset.validate().map_err(|e| format!("{}: {}", path, e))?;
+        Ok(asset)
+    }
+
+    pub fn build(&self, seed: i64) -> GalaxyLayout {
+        match self {
+            GalaxyAsset::Fixed(layout) => layout.clone(),
+            GalaxyAsset::Random(rules) => rules.generate(seed),
+        }
+    }
+
+    pub fn style(&self) -> Option<orient::Style> {
+        match self {
+            GalaxyAsset::Fixed(layout) => layout.style,
+            GalaxyAsset::Random(rules) => rules.style,
+        }
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        match self {
+            GalaxyAsset::Fixed(layout) => layout.validate(),
+            GalaxyAsset::Random(rules) => rules.validate(),
+        }
+    }
+}
+
+impl ClusterLayout {
+    pub fn center(&self) -> Axial {
+        Axial {
+            q: self.center.0,
+            r: self.center.1,
+        }
+    }
+
+    pub fn color(&self) -> Color {
+        to_color(&self.color)
+    }
+}
+
+impl GalaxyLayout {
+    pub fn color(&self) -> Color {
+        to_color(&self.color)
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        if self.radius < 1 {
+            return Err(format!("galaxy radius {} must be at least 1", self.radius));
+        }
+        if self.clusters.is_empty() {
+            return Err("galaxy needs at least one cluster".to_string());
+        }
+
+        let origin = Axial { q: 0, r: 0 };
+        for (idx, cluster) in self.clusters.iter().enumerate() {
+            if cluster.radius < 1 {
+                return Err(format!("cluster {}: radius must be at least 1", idx));
+            }
+            if cluster.center().distance(&origin) + cluster.radius > self.radius {
+                return Err(format!(
+                    "cluster {}: reaches outside of the galaxy radius {}",
+                    idx, self.radius
+                ));
+            }
+            if !(0.0..=MAX_DENSITY).contains(&cluster.density) {
+                return Err(format!(
+                    "cluster {}: density {} is not in 0..={}",
+                    idx, cluster.density, MAX_DENSITY
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Default for GalaxyLayout {
+    // Note: The original hand made shape, one core and six rotated clusters
+    fn default() -> Self {
+        let origin = Axial { q: 0, r: 0 };
+        let offset = Axial { q: 24, r: 0 };
+
+        let mut places = vec![origin];
+        for step in 0..6 {
+            places.push(offset.rotate_around(&origin, step));
+        }
+
+        Self {
+            radius: 38,
+            color: [0.6, 0.4, 0.6, 0.3],
+            clusters: places
+                .iter()
+                .map(|hex| ClusterLayout {
+                    center: (hex.q, hex.r),
+                    radius: 12,
+                    color: [0.8, 0.6, 0.8, 0.2],
+                    density: 1.0,
+                })
+                .collect(),
+            style: None,
+        }
+    }
+}
+
+impl GalaxyRules {
+    // The core sits on the origin, the outer clusters are spread evenly
+    // on rings around it, starting at a random direction.
+    pub fn generate(&self, seed: i64) -> GalaxyLayout {
+        let mut rng = Shift64::new(seed);
+        let origin = Axial { q: 0, r: 0 };
+
+        let mut clusters = vec![ClusterLayout {
+            center: (0, 0),
+            radius: between(&mut rng, self.core),
+            color: self.palette[0],
+            density: betweenf(&mut rng, self.density),
+        }];
+
+        let count = between(&mut rng, self.clusters);
+        let spacing = between(&mut rng, self.spacing);
+        let ring = origin.ring(spacing);
+        let start = between(&mut rng, (0, ring.len() as i32 - 1)) as usize;
+        for idx in 0..count as usize {
+            let hex = ring[(start + idx * ring.len() / count as usize) % ring.len()];
+
+            // Note: Shrink clusters that would reach outside of the galaxy
+            let room = self.radius - spacing;
+            let radius = i32::min(between(&mut rng, self.cluster_radius), room);
+            let color = between(&mut rng, (0, self.palette.len() as i32 - 1)) as usize;
+            clusters.push(ClusterLayout {
+                center: (hex.q, hex.r),
+                radius,
+                color: self.palette[color],
+                density: betweenf(&mut rng, self.density),
+            });
+        }
+
+        GalaxyLayout {
+            radius: self.radius,
+            color: self.color,
+            clusters,
+            style: self.style,
+        }
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        if self.radius < 1 {
+            return Err(format!("galaxy radius {} must be at least 1", self.radius));
+        }
+        let ranges = [
+            ("core", self.core),
+            ("clusters", self.clusters),
+            ("cluster_radius", self.cluster_radius),
+            ("spacing", self.spacing),
+        ];
+        for (name, (lo, hi)) in ranges {
+            if lo > hi || lo < 0 {
+                return Err(format!("{}: ({}, {}) is not a valid range", name, lo, hi));
+            }
+        }
+
+        if self.core.0 < 1 || self.cluster_radius.0 < 1 {
+            return Err("core and cluster_radius must be at least 1".to_string());
+        }
+        // Note: A zero spacing stacks every cluster on the core
+        if self.spacing.0 < 1 {
+            return Err(format!("spacing: {} must be at least 1", self.spacing.0));
+        }
+        if self.core.1 > self.radius || self.spacing.1 >= self.radius {
+            return Err(format!(
+                "core and spacing must fit inside of the galaxy radius {}",
+                self.radius
+            ));
+        }
+        if self.density.0 > self.density.1 || self.density.0 < 0. || self.density.1 > MAX_DENSITY {
+            return Err(format!(
+                "density: {:?} is not in 0..={}",
+                self.density, MAX_DENSITY
+            ));
+        }
+        if self.palette.is_empty() {
+            return Err("palette needs at least one color".to_string());
+        }
+        Ok(())
+    }
+}
+
+fn to_color(c: &[f32; 4]) -> Color {
+    Color::rgba(c[0], c[1], c[2], c[3])
+}
+
+fn between(rng: &mut Shift64, (lo, hi): (i32, i32)) -> i32 {
+    rng.range(lo..hi + 1)
+}
+
+fn betweenf(rng: &mut Shift64, (lo, hi): (f32, f32)) -> f32 {
+    lo + (hi - lo) * rng.unit() as f32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Axial, GalaxyAsset, GalaxyLayout, GalaxyRules};
+
+    const SHIPPED: &str = include_str!("../../../assets/galaxy/default.galaxy.ron");
+
+    fn shipped_rules() -> GalaxyRules {
+        match GalaxyAsset::from_bytes("default.galaxy.ron", SHIPPED.as_bytes()).unwrap() {
+            GalaxyAsset::Random(rules) => rules,
+            GalaxyAsset::Fixed(_) => panic!("the shipped galaxy is rolled from rules"),
+        }
+    }
+
+    #[test]
+    fn galaxy_shipped() {
+        let rules = shipped_rules();
+        assert!(rules.style.is_some());
+        assert!(GalaxyLayout::default().validate().is_ok());
+    }
+
+    #[test]
+    fn galaxy_generate_deterministic() {
+        let rules = shipped_rules();
+        let a = format!("{:?}", rules.generate(42));
+        assert_eq!(a, format!("{:?}", rules.generate(42)));
+        assert_ne!(a, format!("{:?}", rules.generate(43)));
+    }
+
+    #[test]
+    fn galaxy_generate_inside() {
+        let rules = shipped_rules();
+        let origin = Axial { q: 0, r: 0 };
+        for seed in 0..200 {
+            let layout = rules.generate(seed);
+            assert!(layout.validate().is_ok(), "seed {}", seed);
+            let outer = layout.clusters.len() as i32 - 1;
+            assert!(outer >= rules.clusters.0 && outer <= rules.clusters.1);
+            for cluster in layout.clusters.iter() {
+                let reach = cluster.center().distance(&origin) + cluster.radius;
+                assert!(reach <= layout.radius, "seed {}: {:?}", seed, cluster);
+            }
+        }
+    }
+
+    #[test]
+    fn galaxy_bad_rules() {
+        let mut rules = shipped_rules();
+        rules.spacing = (0, 4);
+        assert!(rules.validate().unwrap_err().contains("spacing"));
+        rules.spacing = (-2, 4);
+        assert!(rules.validate().is_err());
+
+        let mut rules = shipped_rules();
+        rules.radius = 0;
+        assert!(rules.validate().unwrap_err().contains("radius"));
+
+        let source = SHIPPED.replace("spacing: (22, 26)", "spacing: (0, 26)");
+        let err = GalaxyAsset::from_bytes("bad.galaxy.ron", source.as_bytes()).unwrap_err();
+        assert!(err.starts_with("bad.galaxy.ron"), "{}", err);
+        assert!(err.contains("spacing"), "{}", err);
+    }
+}
@@ -1,4 +1,5 @@
 use std::fmt::Display;
+use std::ops::Add;
 
 use super::*;
 
@@ -58,6 +59,15 @@ impl Diagonal {
 }
 
 impl Direction {
+    pub const ALL: [Direction; 6] = [
+        Direction::QPos,
+        Direction::RNeg,
+        Direction::SPos,
+        Direction::QNeg,
+        Direction::RPos,
+        Direction::SNeg,
+    ];
+
     pub fn delta(&self) -> Cuboid {
         match self {
             Direction::QNeg => Cuboid { q: -1, r: 0, s: 1 },
@@ -96,6 +106,79 @@ impl Axial {
         }
     }
 
+    pub fn neighbors(&self) -> [Axial; 6] {
+        Direction::ALL.map(|d| self.neighbor(d))
+    }
+
+    // Hexes at exactly radius steps, walking the six edges of the ring
+    pub fn ring(&self, radius: i32) -> Vec<Axial> {
+        if radius < 1 {
+            return match radius {
+                0 => vec![*self],
+                _ => Vec::new(),
+            };
+        }
+
+        let mut result = Vec::with_capacity(6 * radius as usize);
+        let mut hex = *self + Direction::RPos.delta().scale(radius);
+        for d in Direction::ALL {
+            for _ in 0..radius {
+                result.push(hex);
+                hex = hex + d.delta();
+            }
+        }
+        result
+    }
+
+    // All hexes up to radius, ordered ring by ring from the center
+    pub fn spiral(&self, radius: i32) -> Vec<Axial> {
+        let mut result = Vec::new();
+        for k in 0..(radius + 1) {
+            result.append(&mut self.ring(k));
+        }
+        result
+    }
+
+    // All hexes up to radius, ordered by q then r
+    pub fn range(&self, radius: i32) -> Vec<Axial> {
+        Axial::intersect(self, radius, self, radius)
+    }
+
+    // Hexes inside of both ranges, empty if they do not overlap
+    pub fn intersect(a: &Axial, ra: i32, b: &Axial, rb: i32) -> Vec<Axial> {
+        let (ca, cb) = (a.cuboid(), b.cuboid());
+        let (q_min, q_max) = (
+            i32::max(ca.q - ra, cb.q - rb),
+            i32::min(ca.q + ra, cb.q + rb),
+        );
+        let (r_min, r_max) = (
+            i32::max(ca.r - ra, cb.r - rb),
+            i32::min(ca.r + ra, cb.r + rb),
+        );
+        let (s_min, s_max) = (
+            i32::max(ca.s - ra, cb.s - rb),
+            i32::min(ca.s + ra, cb.s + rb),
+        );
+
+        let mut result = Vec::new();
+        for q in q_min..(q_max + 1) {
+            let r1 = i32::max(r_min, -q - s_max);
+            let r2 = i32::min(r_max, -q - s_min);
+            for r in r1..(r2 + 1) {
+                result.push(Axial { q, r });
+            }
+        }
+        result
+    }
+
+    pub fn cuboid(&self) -> Cuboid {
+        Cuboid {
+            q: self.q,
+            r: self.r,
+            s: -self.q - self.r,
+        }
+    }
+
     pub fn line(a: &Axial, b: &Axial) -> Vec<Axial> {
         let delta = a.delta(b);
 
@@ -128,6 +211,17 @@ impl Axial {
     }
 }
 
+impl Add<Cuboid> for Axial {
+    type Output = Axial;
+
+    fn add(self, rh: Cuboid) -> Axial {
+        Axial {
+            q: self.q + rh.q,
+            r: self.r + rh.r,
+        }
+    }
+}
+
 impl Display for Axial {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "({}:{})", self.q, self.r)
@@ -143,6 +237,14 @@ impl Cuboid {
         }
     }
 
+    pub fn scale(&self, k: i32) -> Cuboid {
+        Cuboid {
+            q: self.q * k,
+            r: self.r * k,
+            s: self.s * k,
+        }
+    }
+
     pub fn length(&self) -> i32 {
         (i32::abs(self.q) + i32::abs(self.r) + i32::abs(self.s)) >> 1
     }
@@ -196,3 +298,75 @@ pub(crate) fn f32_to_axial(x: f32, y: f32, z: f32) -> Axial {
         r: f32::round(rz) as i32,
     };
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{Axial, Direction};
+
+    const CENTER: Axial = Axial { q: 2, r: -3 };
+
+    #[test]
+    fn axial_ring() {
+        assert_eq!(CENTER.ring(0), vec![CENTER]);
+        assert_eq!(CENTER.ring(-1), vec![]);
+
+        for radius in 1..8 {
+            let ring = CENTER.ring(radius);
+            assert_eq!(ring.len(), 6 * radius as usize);
+            for (idx, hex) in ring.iter().enumerate() {
+                assert_eq!(hex.distance(&CENTER), radius);
+                // Note: Each hex on the ring touches the next one
+                let next = ring[(idx + 1) % ring.len()];
+                assert_eq!(hex.distance(&next), 1);
+            }
+        }
+
+        let mut ring = CENTER.ring(1);
+        let mut neighbors = CENTER.neighbors().to_vec();
+        ring.sort_by_key(|h| (h.q, h.r));
+        neighbors.sort_by_key(|h| (h.q, h.r));
+        assert_eq!(ring, neighbors);
+    }
+
+    #[test]
+    fn axial_spiral() {
+        let spiral = CENTER.spiral(4);
+        assert_eq!(spiral.len(), 61);
+        assert_eq!(spiral[0], CENTER);
+
+        let mut last = 0;
+        for hex in spiral.iter() {
+            let dist = hex.distance(&CENTER);
+            assert!(dist >= last);
+            last = dist;
+        }
+    }
+
+    #[test]
+    fn axial_range() {
+        for radius in 0..8 {
+            let range = CENTER.range(radius);
+            assert_eq!(range.len() as i32, 3 * radius * (radius + 1) + 1);
+            assert!(range.iter().all(|h| h.distance(&CENTER) <= radius));
+
+            let mut spiral = CENTER.spiral(radius);
+            spiral.sort_by_key(|h| (h.q, h.r));
+            assert_eq!(range, spiral);
+        }
+    }
+
+    #[test]
+    fn axial_intersect() {
+        let a = Axial { q: 0, r: 0 };
+        let b = a + Direction::QPos.delta().scale(4);
+
+        let both = Axial::intersect(&a, 3, &b, 2);
+        let mut brute = a.range(3);
+        brute.retain(|h| h.distance(&b) <= 2);
+        assert_eq!(both.len(), 4);
+        assert_eq!(both, brute);
+
+        assert!(Axial::intersect(&a, 1, &b, 2).is_empty());
+        assert_eq!(Axial::intersect(&a, 2, &a, 5), a.range(2));
+    }
+}
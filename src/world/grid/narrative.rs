@@ -0,0 +1,220 @@
+use serde::Deserialize;
+
+use super::*;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize)]
+pub enum Stat {
+    Health,
+    Energy,
+    Cargo,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize)]
+pub enum Condition {
+    AtLeast(Stat, u16),
+    Below(Stat, u16),
+    Flag(String),
+    NotFlag(String),
+    Counter(String, u16),
+    Chapter(usize), // Reached at least this chapter
+    Cleared(u16),   // Events cleared in the current chapter
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize)]
+pub enum Outcome {
+    Gain(Stat, u16),
+    Lose(Stat, u16),
+    Combat(CombatAction),
+    Reveal(i32), // Scan radius around the ship
+    SetFlag(String),
+    ClrFlag(String),
+    Count(String, u16),
+    Page(usize), // Follow-up page, 0 is the opening page
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct EventChoice {
+    pub label: String,
+    #[serde(default)]
+    pub conditions: Vec<Condition>,
+    #[serde(default)]
+    pub outcomes: Vec<Outcome>,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct EventPage {
+    pub title: String,
+    pub descr: String,
+    pub choices: Vec<EventChoice>,
+}
+
+// Snapshot of the ship the conditions are checked against
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct PlayerStats {
+    pub health: u16,
+    pub energy: u16,
+    pub cargo: u16,
+}
+
+impl PlayerStats {
+    pub fn new(health: &HealthRecource, energy: &EnergyRecource, cargo: &CargoRecource) -> Self {
+        Self {
+            health: health.value,
+            energy: energy.value,
+            cargo: cargo.value,
+        }
+    }
+
+    pub fn get(&self, stat: Stat) -> u16 {
+        match stat {
+            Stat::Health => self.health,
+            Stat::Energy => self.energy,
+            Stat::Cargo => self.cargo,
+        }
+    }
+}
+
+impl Stat {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Stat::Health => "hp",
+            Stat::Energy => "energy",
+            Stat::Cargo => "cargo",
+        }
+    }
+}
+
+impl Condition {
+    pub fn check(&self, stats: &PlayerStats, story: &StoryState) -> bool {
+        match self {
+            Condition::AtLeast(stat, value) => stats.get(*stat) >= *value,
+            Condition::Below(stat, value) => stats.get(*stat) < *value,
+            Condition::Flag(flag) => story.has_flag(flag),
+            Condition::NotFlag(flag) => !story.has_flag(flag),
+            Condition::Counter(name, value) => story.counter(name) >= *value,
+            Condition::Chapter(chapter) => story.chapter >= *chapter,
+            Condition::Cleared(value) => story.progress >= *value,
+        }
+    }
+
+    // Note: Choices behind the story state stay secret until they are unlocked
+    pub fn is_hidden(&self) -> bool {
+        !matches!(self, Condition::AtLeast(..) | Condition::Below(..))
+    }
+
+    pub fn describe(&self) -> String {
+        match self {
+            Condition::AtLeast(stat, value) => format!("{} {}+", stat.label(), value),
+            Condition::Below(stat, value) => format!("{} below {}", stat.label(), value),
+            Condition::Flag(flag) => format!("needs {}", flag),
+            Condition::NotFlag(flag) => format!("not {}", flag),
+            Condition::Counter(name, value) => format!("{} {}+", name, value),
+            Condition::Chapter(chapter) => format!("chapter {}", chapter + 1),
+            Condition::Cleared(value) => format!("{} cleared", value),
+        }
+    }
+}
+
+impl EventChoice {
+    pub fn is_open(&self, stats: &PlayerStats, story: &StoryState) -> bool {
+        self.conditions.iter().all(|c| c.check(stats, story))
+    }
+
+    pub fn is_hidden(&self, stats: &PlayerStats, story: &StoryState) -> bool {
+        self.conditions
+            .iter()
+            .any(|c| c.is_hidden() && !c.check(stats, story))
+    }
+
+    // Button text, locked choices show the first condition they miss
+    pub fn text(&self, stats: &PlayerStats, story: &StoryState) -> String {
+        match self.conditions.iter().find(|c| !c.check(stats, story)) {
+            Some(condition) => format!("{} ({})", self.label, condition.describe()),
+            None => self.label.clone(),
+        }
+    }
+}
+
+impl EventPage {
+    pub fn validate(&self, pages: usize) -> Result<(), String> {
+        if self.choices.is_empty() {
+            return Err(format!("page {:?} has no choices", self.title));
+        }
+        if self.choices.iter().all(|c| !c.conditions.is_empty()) {
+            return Err(format!(
+                "page {:?} has no choice without conditions",
+                self.title
+            ));
+        }
+        for choice in self.choices.iter() {
+            if choice.label.trim().is_empty() {
+                return Err(format!("page {:?}: choice label is empty", self.title));
+            }
+            for outcome in choice.outcomes.iter() {
+                if let Outcome::Page(page) = outcome {
+                    if *page >= pages {
+                        return Err(format!(
+                            "page {:?}: choice {:?} points to missing page {}",
+                            self.title, choice.label, page
+                        ));
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Condition, EventChoice, EventPage, Outcome, PlayerStats, Stat, StoryState};
+
+    fn choice(label: &str, conditions: Vec<Condition>, outcomes: Vec<Outcome>) -> EventChoice {
+        EventChoice {
+            label: label.to_string(),
+            conditions,
+            outcomes,
+        }
+    }
+
+    #[test]
+    fn choice_conditions() {
+        let mut story = StoryState::default();
+        let stats = PlayerStats {
+            health: 40,
+            energy: 8,
+            cargo: 0,
+        };
+
+        let pay = choice("pay", vec![Condition::AtLeast(Stat::Energy, 10)], vec![]);
+        assert!(!pay.is_open(&stats, &story));
+        assert!(!pay.is_hidden(&stats, &story));
+        assert_eq!(pay.text(&stats, &story), "pay (energy 10+)");
+
+        let secret = choice("hail", vec![Condition::Flag("beacon".into())], vec![]);
+        assert!(secret.is_hidden(&stats, &story));
+        story.set_flag("beacon");
+        assert!(secret.is_open(&stats, &story));
+        assert_eq!(secret.text(&stats, &story), "hail");
+    }
+
+    #[test]
+    fn page_validate() {
+        let leave = choice("leave", vec![], vec![]);
+        let next = choice("follow", vec![], vec![Outcome::Page(2)]);
+        let mut page = EventPage {
+            title: "Signal".to_string(),
+            descr: "".to_string(),
+            choices: vec![leave, next],
+        };
+        assert!(page.validate(3).is_ok());
+        assert!(page.validate(2).is_err());
+
+        page.choices = vec![choice(
+            "pay",
+            vec![Condition::Below(Stat::Health, 5)],
+            vec![],
+        )];
+        assert!(page.validate(1).is_err());
+    }
+}
@@ -1,6 +1,8 @@
+use serde::Deserialize;
+
 use super::*;
 
-#[derive(Clone, Copy, Debug, Default)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize)]
 pub enum Style {
     #[default]
     Flat,
@@ -76,7 +78,7 @@ impl Orientation {
     }
 }
 impl Layout {
-    pub fn new(size: Vec2, style: Style, origin: Vec2, radius: i32) -> Self {
+    pub fn new(size: Vec2, style: Style, origin: Vec2) -> Self {
         Layout {
             size,
             style,
@@ -116,86 +118,65 @@ impl Layout {
         };
     }
 
-    pub fn area_for(&self, center: Axial, rad: f32) -> HashMap<Axial, bool> {
-        let ring = self.ring_for(&center, rad);
-        let mut result = HashMap::new();
-        for key in ring.keys() {
-            result.insert(key.clone(), true);
-            for val in Axial::line(key, &center) {
-                result.insert(val, true);
-            }
+    // Note: Hex textures are pointy, flat hexes use the rotated sprite
+    pub fn sprite_rotation(&self) -> Quat {
+        match self.style {
+            Style::Flat => Quat::from_rotation_z(PI / 2.),
+            Style::Pointy => Quat::IDENTITY,
         }
-        return result;
     }
 
-    pub fn ring_for(&self, center: &Axial, rad: f32) -> HashMap<Axial, bool> {
-        let mut result = HashMap::new();
-        if rad < self.size.x && rad < self.size.y {
-            result.insert(center.clone(), true);
-            return result;
-        }
-        let cp = self.center_for(center);
+    pub fn is_in_range(&self, p: Vec2, radius: i32) -> bool {
+        let h = self.hex_for(p);
+        h.distance(&Axial { q: 0, r: 0 }) < radius
+    }
+}
 
-        let mut px = Vec2 { x: rad, y: 0. };
-        let mut p = 1. - rad;
+#[cfg(test)]
+mod tests {
+    use super::{Axial, Layout, Style, Vec2};
 
-        while px.x > px.y {
-            if p <= 0. {
-                p = p + 2. * px.y + 1.
-            } else {
-                px.x = px.x - 1.;
-                p = p + 2. * px.y - 2. * px.x + 1.
-            }
+    fn layout(style: Style) -> Layout {
+        Layout::new(Vec2 { x: 64., y: 64. }, style, Vec2 { x: 32., y: -16. })
+    }
 
-            if px.x < px.y {
-                px.y += 1.;
-                break;
-            }
+    fn round_trip(style: Style) {
+        let layout = layout(style);
+        for hex in (Axial { q: 0, r: 0 }).range(12) {
+            let center = layout.center_for(&hex);
+            assert_eq!(layout.hex_for(center), hex);
 
-            let points: [Vec2; 8] = [
-                Vec2 {
-                    x: px.x + cp.x,
-                    y: px.y + cp.y,
-                },
-                Vec2 {
-                    x: -px.x + cp.x,
-                    y: px.y + cp.y,
-                },
-                Vec2 {
-                    x: px.x + cp.x,
-                    y: -px.y + cp.y,
-                },
-                Vec2 {
-                    x: -px.x + cp.x,
-                    y: -px.y + cp.y,
-                },
-                Vec2 {
-                    x: px.y + cp.x,
-                    y: px.x + cp.y,
-                },
-                Vec2 {
-                    x: -px.y + cp.x,
-                    y: px.x + cp.y,
-                },
-                Vec2 {
-                    x: px.y + cp.x,
-                    y: -px.x + cp.y,
-                },
-                Vec2 {
-                    x: -px.y + cp.x,
-                    y: -px.x + cp.y,
-                },
-            ];
-            for v in points {
-                result.insert(self.hex_for(v), true);
+            // Note: Points inside of the hex still pick the same hex
+            for offset in [
+                Vec2 { x: 20., y: 0. },
+                Vec2 { x: -20., y: 0. },
+                Vec2 { x: 0., y: 20. },
+                Vec2 { x: 0., y: -20. },
+            ] {
+                assert_eq!(layout.hex_for(center + offset), hex);
             }
-            px.y += 1.;
         }
-        return result;
     }
 
-    pub fn is_in_range(&self, p: Vec2, radius: i32) -> bool {
-        let h = self.hex_for(p);
-        h.distance(&Axial { q: 0, r: 0 }) < radius
+    #[test]
+    fn layout_round_trip_flat() {
+        round_trip(Style::Flat);
+    }
+
+    #[test]
+    fn layout_round_trip_pointy() {
+        round_trip(Style::Pointy);
+    }
+
+    #[test]
+    fn layout_neighbor_spacing() {
+        for style in [Style::Flat, Style::Pointy] {
+            let layout = layout(style);
+            let center = layout.center_for(&Axial { q: 0, r: 0 });
+            for hex in (Axial { q: 0, r: 0 }).neighbors() {
+                let dist = center.distance(layout.center_for(&hex));
+                assert!((dist - 64. * f32::sqrt(3.)).abs() < 0.01);
+            }
+        }
     }
 }
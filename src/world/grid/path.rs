@@ -0,0 +1,244 @@
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+
+use super::*;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct HexJump {
+    pub hex: Axial,
+    pub cost: i32,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct HexPath {
+    pub hexes: Vec<Axial>,
+    pub costs: Vec<i32>,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+struct Frontier {
+    priority: i32,
+    order: u32,
+    hex: Axial,
+}
+
+impl Ord for Frontier {
+    fn cmp(&self, other: &Self) -> Ordering {
+        // Note: Reversed for a min-heap, ties resolve in insertion order
+        other
+            .priority
+            .cmp(&self.priority)
+            .then_with(|| other.order.cmp(&self.order))
+    }
+}
+
+impl PartialOrd for Frontier {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl HexPath {
+    // A* search from start to goal, `cost` returns the price to enter
+    // a hex (at least 1) or None if the hex can not be entered.
+    // The search gives up once the total cost would exceed the limit.
+    pub fn find<F>(start: &Axial, goal: &Axial, limit: i32, cost: F) -> Option<HexPath>
+    where
+        F: Fn(&Axial) -> Option<i32>,
+    {
+        let mut order = 0;
+        let mut open = BinaryHeap::new();
+        let mut came_from: HashMap<Axial, Axial> = HashMap::new();
+        let mut cost_so_far: HashMap<Axial, i32> = HashMap::new();
+
+        open.push(Frontier {
+            priority: 0,
+            order,
+            hex: *start,
+        });
+        cost_so_far.insert(*start, 0);
+
+        while let Some(Frontier { hex, .. }) = open.pop() {
+            if hex == *goal {
+                return Some(Self::rebuild(start, goal, &came_from, &cost_so_far));
+            }
+
+            let current = cost_so_far[&hex];
+            for next in hex.neighbors() {
+                let step = match cost(&next) {
+                    Some(step) => i32::max(step, 1),
+                    None => continue,
+                };
+
+                let total = current + step;
+                if total > limit {
+                    continue;
+                }
+                if let Some(known) = cost_so_far.get(&next) {
+                    if *known <= total {
+                        continue;
+                    }
+                }
+
+                order += 1;
+                cost_so_far.insert(next, total);
+                came_from.insert(next, hex);
+                open.push(Frontier {
+                    priority: total + next.distance(goal),
+                    order,
+                    hex: next,
+                });
+            }
+        }
+        None
+    }
+
+    fn rebuild(
+        start: &Axial,
+        goal: &Axial,
+        came_from: &HashMap<Axial, Axial>,
+        cost_so_far: &HashMap<Axial, i32>,
+    ) -> HexPath {
+        let mut hexes = vec![*goal];
+        let mut current = *goal;
+        while current != *start {
+            current = came_from[&current];
+            hexes.push(current);
+        }
+        hexes.reverse();
+
+        // Note: Cost to enter each hex, the start is free
+        let mut costs = Vec::with_capacity(hexes.len());
+        let mut last = 0;
+        for hex in hexes.iter() {
+            let total = cost_so_far[hex];
+            costs.push(total - last);
+            last = total;
+        }
+
+        HexPath { hexes, costs }
+    }
+
+    pub fn steps(&self) -> i32 {
+        i32::max(self.hexes.len() as i32 - 1, 0)
+    }
+
+    pub fn cost(&self) -> i32 {
+        self.costs.iter().sum()
+    }
+
+    pub fn energy(&self, per_hex: i32) -> i32 {
+        self.cost() * per_hex
+    }
+
+    // Split the path in to legal jumps of at most `range` hexes,
+    // the last jump always lands on the goal.
+    pub fn jumps(&self, range: i32) -> Vec<HexJump> {
+        let mut result = Vec::new();
+        if range < 1 {
+            return result;
+        }
+
+        let mut cost = 0;
+        let mut hops = 0;
+        for (idx, hex) in self.hexes.iter().enumerate().skip(1) {
+            cost += self.costs[idx];
+            hops += 1;
+            if hops == range || idx == self.hexes.len() - 1 {
+                result.push(HexJump { hex: *hex, cost });
+                (cost, hops) = (0, 0);
+            }
+        }
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Axial, HexJump, HexPath};
+
+    const ORIGIN: Axial = Axial { q: 0, r: 0 };
+
+    fn is_connected(path: &HexPath) -> bool {
+        path.hexes
+            .windows(2)
+            .all(|pair| pair[0].distance(&pair[1]) == 1)
+    }
+
+    #[test]
+    fn path_straight() {
+        let goal = Axial { q: 5, r: 0 };
+        let path = HexPath::find(&ORIGIN, &goal, 20, |_| Some(1)).unwrap();
+        assert_eq!(path.hexes.first(), Some(&ORIGIN));
+        assert_eq!(path.hexes.last(), Some(&goal));
+        assert!(is_connected(&path));
+        assert_eq!(path.steps(), 5);
+        assert_eq!(path.cost(), 5);
+        assert_eq!(path.costs[0], 0);
+        assert_eq!(path.energy(4), 20);
+    }
+
+    #[test]
+    fn path_detour() {
+        // Note: A wall across the straight line, the grid ends at radius 4
+        let goal = Axial { q: 4, r: 0 };
+        let blocked = |hex: &Axial| hex.q == 2 && hex.r > -3 && hex.r < 2;
+        let cost = |hex: &Axial| match blocked(hex) || hex.distance(&ORIGIN) > 4 {
+            true => None,
+            false => Some(1),
+        };
+
+        let path = HexPath::find(&ORIGIN, &goal, 20, cost).unwrap();
+        assert_eq!(path.hexes.last(), Some(&goal));
+        assert!(is_connected(&path));
+        assert!(path.steps() > ORIGIN.distance(&goal));
+        for hex in path.hexes.iter() {
+            assert!(!blocked(hex), "{:?}", hex);
+            assert!(hex.distance(&ORIGIN) <= 4, "{:?}", hex);
+        }
+    }
+
+    #[test]
+    fn path_unreachable() {
+        // Every neighbor of the goal is blocked
+        let goal = Axial { q: 3, r: -1 };
+        let walled = |hex: &Axial| match hex.distance(&goal) == 1 {
+            true => None,
+            false => Some(1),
+        };
+        assert!(HexPath::find(&ORIGIN, &goal, 40, walled).is_none());
+
+        // The goal is further away than the limit allows
+        assert!(HexPath::find(&ORIGIN, &goal, 2, |_| Some(1)).is_none());
+    }
+
+    #[test]
+    fn path_jumps() {
+        let goal = Axial { q: 9, r: 0 };
+        let path = HexPath::find(&ORIGIN, &goal, 20, |_| Some(1)).unwrap();
+        let costs: Vec<i32> = path.jumps(4).iter().map(|jump| jump.cost).collect();
+        assert_eq!(costs, vec![4, 4, 1]);
+        assert!(path.jumps(0).is_empty());
+
+        // Costs of the entered hexes add up per jump
+        let path = HexPath {
+            hexes: Axial::line(&ORIGIN, &goal),
+            costs: vec![0, 1, 1, 2, 1, 1, 1, 3, 1, 1],
+        };
+        assert_eq!(path.steps(), 9);
+        assert_eq!(
+            path.jumps(4),
+            vec![
+                HexJump {
+                    hex: Axial { q: 4, r: 0 },
+                    cost: 5
+                },
+                HexJump {
+                    hex: Axial { q: 8, r: 0 },
+                    cost: 6
+                },
+                HexJump { hex: goal, cost: 1 },
+            ]
+        );
+    }
+}
@@ -0,0 +1,110 @@
+use super::*;
+
+// What an event may touch once the player enters it
+pub struct EnterContext<'a, 'w, 's> {
+    pub commands: &'a mut Commands<'w, 's>,
+    pub rng: &'a mut Shift64,
+    pub assets: &'a WorldAssets,
+    pub player: &'a mut Player,
+    pub center: Vec2,
+}
+
+// What an event may touch once the player leaves it without entering
+pub struct ResolveContext<'a> {
+    pub health: &'a mut HealthRecource,
+    pub energy: &'a mut EnergyRecource,
+}
+
+pub type EnterFn = fn(&mut EnterContext, &EventPayload);
+pub type ResolveFn = fn(&mut ResolveContext, &EventPayload);
+
+pub struct EventKind {
+    pub actions: EventAction,
+    pub table: WeightedTable<EventInfo<EventPayload>>,
+    pub enter: Option<EnterFn>,
+    pub resolve: Option<ResolveFn>,
+}
+
+// Note: New event kinds only need a payload, a table and a register call
+#[derive(Default)]
+pub struct EventRegistry {
+    kinds: HashMap<EventKey, EventKind>,
+}
+
+impl EventRegistry {
+    pub fn register(
+        &mut self,
+        key: EventKey,
+        actions: EventAction,
+        enter: Option<EnterFn>,
+        resolve: Option<ResolveFn>,
+    ) {
+        let table = match self.kinds.remove(&key) {
+            Some(kind) => kind.table,
+            None => WeightedTable::new(),
+        };
+        self.kinds.insert(
+            key,
+            EventKind {
+                actions,
+                table,
+                enter,
+                resolve,
+            },
+        );
+    }
+
+    pub fn get(&self, key: EventKey) -> Option<&EventKind> {
+        self.kinds.get(&key)
+    }
+
+    pub fn actions(&self, key: EventKey) -> EventAction {
+        match self.kinds.get(&key) {
+            Some(kind) => kind.actions.clone(),
+            None => EventAction::new("enter", "leave"),
+        }
+    }
+
+    // Note: Tables are merged by their kind, a kind may be split over files
+    pub fn load_tables<'a>(&mut self, tables: impl Iterator<Item = &'a EventTable>) {
+        for kind in self.kinds.values_mut() {
+            kind.table = WeightedTable::new();
+        }
+
+        for table in tables {
+            let kind = match self.kinds.get_mut(&table.kind) {
+                Some(kind) => kind,
+                None => {
+                    log::warn!("load_tables: {:?} is not registered", table.kind);
+                    continue;
+                }
+            };
+            for entry in table.events.iter() {
+                kind.table.push(entry.info(), entry.weight, entry.rarity);
+            }
+        }
+    }
+
+    pub fn roll(
+        &self,
+        key: EventKey,
+        seed: i64,
+        modifier: &RollModifier,
+    ) -> Option<EventInfo<EventPayload>> {
+        let kind = self.kinds.get(&key)?;
+        let mut rng = Shift64::new(seed);
+        kind.table.roll(&mut rng, modifier).cloned()
+    }
+
+    pub fn enter(&self, ctx: &mut EnterContext, payload: &EventPayload) {
+        if let Some(enter) = self.get(payload.key()).and_then(|k| k.enter) {
+            enter(ctx, payload);
+        }
+    }
+
+    pub fn resolve(&self, ctx: &mut ResolveContext, payload: &EventPayload) {
+        if let Some(resolve) = self.get(payload.key()).and_then(|k| k.resolve) {
+            resolve(ctx, payload);
+        }
+    }
+}
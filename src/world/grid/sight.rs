@@ -0,0 +1,72 @@
+use super::*;
+
+// The target is visible when no hex between a and b blocks, the
+// blocking hex itself can still be seen.
+pub fn has_line_of_sight<F>(a: &Axial, b: &Axial, blocks: F) -> bool
+where
+    F: Fn(&Axial) -> bool,
+{
+    Axial::line(a, b)
+        .iter()
+        .filter(|hex| *hex != a && *hex != b)
+        .all(|hex| !blocks(hex))
+}
+
+// Visible hexes within radius of the center, the center is always visible
+pub fn field_of_view<F>(center: &Axial, radius: i32, blocks: F) -> HashSet<Axial>
+where
+    F: Fn(&Axial) -> bool,
+{
+    let mut result = HashSet::new();
+    for hex in center.range(radius) {
+        if has_line_of_sight(center, &hex, &blocks) {
+            result.insert(hex);
+        }
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{field_of_view, has_line_of_sight, orient, Axial, EventKey, HexMap, HexNode, Vec2};
+
+    const ORIGIN: Axial = Axial { q: 0, r: 0 };
+    const BELT: Axial = Axial { q: 2, r: 0 };
+
+    fn map_with_belt() -> HexMap<HexNode> {
+        let mut map = HexMap::new(Vec2::splat(1.), orient::Style::Pointy, Vec2::ZERO, 6);
+        map.fill_with(|_| HexNode::default());
+        map.get_mut(&BELT).unwrap().key = EventKey::Mining;
+        map
+    }
+
+    #[test]
+    fn sight_blocked_by_belt() {
+        let map = map_with_belt();
+        let blocks = |hex: &Axial| map.get(hex).map_or(false, |node| node.blocks_sight());
+
+        assert!(has_line_of_sight(&ORIGIN, &BELT, blocks));
+        assert!(!has_line_of_sight(&ORIGIN, &Axial { q: 3, r: 0 }, blocks));
+        assert!(!has_line_of_sight(&ORIGIN, &Axial { q: 5, r: 0 }, blocks));
+        assert!(has_line_of_sight(&ORIGIN, &Axial { q: 0, r: 3 }, blocks));
+        assert!(has_line_of_sight(&BELT, &Axial { q: 3, r: 0 }, blocks));
+    }
+
+    #[test]
+    fn sight_field_of_view() {
+        let map = map_with_belt();
+        let blocks = |hex: &Axial| map.get(hex).map_or(false, |node| node.blocks_sight());
+
+        let open = field_of_view(&ORIGIN, 3, |_| false);
+        assert_eq!(open.len(), ORIGIN.range(3).len());
+
+        let view = field_of_view(&ORIGIN, 3, blocks);
+        assert!(view.contains(&ORIGIN));
+        assert!(view.contains(&BELT));
+        assert!(!view.contains(&Axial { q: 3, r: 0 }));
+        assert!(view.contains(&Axial { q: 0, r: 3 }));
+        assert!(!view.contains(&Axial { q: 0, r: 4 }));
+        assert!(view.iter().all(|hex| hex.distance(&ORIGIN) <= 3));
+        assert!(view.len() < open.len());
+    }
+}
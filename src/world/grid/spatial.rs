@@ -0,0 +1,132 @@
+use super::*;
+
+// Broad phase for circle overlaps, entries are bucketed by the hex they sit on
+#[derive(Clone, Debug)]
+pub struct SpatialHash<T> {
+    layout: Layout,
+    buckets: HashMap<Axial, Vec<(T, Vec2, f32)>>,
+    largest: f32, // Biggest radius inserted since the last clear
+}
+
+impl<T: Copy> Default for SpatialHash<T> {
+    fn default() -> Self {
+        Self::new(TILE_SIZE)
+    }
+}
+
+impl<T: Copy> SpatialHash<T> {
+    pub fn new(cell: f32) -> Self {
+        Self {
+            layout: Layout::new(Vec2::splat(cell), Style::Flat, Vec2::ZERO),
+            buckets: HashMap::default(),
+            largest: 0.,
+        }
+    }
+
+    // Note: Buckets keep their memory, only the ones left empty are dropped
+    pub fn clear(&mut self) {
+        self.buckets.retain(|_, bucket| {
+            let used = !bucket.is_empty();
+            bucket.clear();
+            used
+        });
+        self.largest = 0.;
+    }
+
+    pub fn insert(&mut self, item: T, position: Vec2, radius: f32) {
+        let hex = self.layout.hex_for(position);
+        self.buckets
+            .entry(hex)
+            .or_insert_with(Vec::new)
+            .push((item, position, radius));
+        self.largest = f32::max(self.largest, radius);
+    }
+
+    pub fn len(&self) -> usize {
+        self.buckets.values().map(|bucket| bucket.len()).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.buckets.values().all(|bucket| bucket.is_empty())
+    }
+
+    // Every entry whose circle touches the given one, with its distance
+    pub fn overlaps(&self, position: Vec2, radius: f32) -> impl Iterator<Item = (T, f32)> + '_ {
+        // Note: A point is at most one cell size from its hex center and
+        // centers n hexes apart are at least 1.5 cell sizes per step away
+        let cell = self.layout.size.x;
+        let reach = (radius + self.largest + 2. * cell) / (1.5 * cell);
+        let center = self.layout.hex_for(position);
+
+        center
+            .range(reach.ceil() as i32)
+            .into_iter()
+            .filter_map(|hex| self.buckets.get(&hex))
+            .flat_map(|bucket| bucket.iter())
+            .filter_map(move |(item, other, size)| {
+                let dist = position.distance(*other);
+                match dist < radius + size {
+                    true => Some((*item, dist)),
+                    false => None,
+                }
+            })
+    }
+
+    // The closest overlapping entry the filter accepts
+    pub fn closest(&self, position: Vec2, radius: f32, filter: impl Fn(&T) -> bool) -> Option<T> {
+        self.overlaps(position, radius)
+            .filter(|(item, _)| filter(item))
+            .min_by(|a, b| a.1.total_cmp(&b.1))
+            .map(|(item, _)| item)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Shift64, SpatialHash, Vec2};
+
+    #[test]
+    fn spatial_matches_brute_force() {
+        let mut rng = Shift64::new(23);
+        let mut hash = SpatialHash::new(64.);
+        let mut items = Vec::new();
+        for idx in 0..300 {
+            let position = Vec2::new(rng.f32(2000.) - 1000., rng.f32(2000.) - 1000.);
+            let radius = rng.f32(40.);
+            hash.insert(idx, position, radius);
+            items.push((idx, position, radius));
+        }
+        assert_eq!(hash.len(), 300);
+
+        for _ in 0..200 {
+            let position = Vec2::new(rng.f32(2000.) - 1000., rng.f32(2000.) - 1000.);
+            let radius = rng.f32(120.);
+            let mut found: Vec<usize> = hash.overlaps(position, radius).map(|(i, _)| i).collect();
+            let mut expected: Vec<usize> = items
+                .iter()
+                .filter(|(_, other, size)| position.distance(*other) < radius + size)
+                .map(|(i, _, _)| *i)
+                .collect();
+            found.sort();
+            expected.sort();
+            assert_eq!(found, expected);
+        }
+
+        hash.clear();
+        assert!(hash.is_empty());
+        assert_eq!(hash.overlaps(Vec2::ZERO, 2000.).count(), 0);
+    }
+
+    #[test]
+    fn spatial_closest() {
+        let mut hash = SpatialHash::new(64.);
+        hash.insert(1, Vec2::new(10., 0.), 16.);
+        hash.insert(2, Vec2::new(4., 0.), 16.);
+        hash.insert(3, Vec2::new(200., 0.), 64.);
+
+        assert_eq!(hash.closest(Vec2::ZERO, 0., |_| true), Some(2));
+        assert_eq!(hash.closest(Vec2::ZERO, 0., |i| *i != 2), Some(1));
+        assert_eq!(hash.closest(Vec2::new(140., 0.), 0., |_| true), Some(3));
+        assert_eq!(hash.closest(Vec2::new(100., 0.), 0., |_| true), None);
+    }
+}
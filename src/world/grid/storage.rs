@@ -1,291 +1,220 @@
 use super::*;
 
-const ITERATIONS: i32 = 96;
-const MAX_CHECKS: i32 = 32;
+#[derive(Clone)]
+pub struct HexMap<T> {
+    pub radius: i32,
+    pub layout: Layout,
+    nodes: HashMap<Axial, T>,
+}
 
-const MIN_RADIUS: f32 = 192. + 64.;
-const AVR_RADIUS: f32 = 256. + 64.;
-const MAX_RADIUS: f32 = 384. + 64.;
+impl<T> HexMap<T> {
+    pub fn new(size: Vec2, style: orient::Style, origin: Vec2, radius: i32) -> Self {
+        Self {
+            radius: radius,
+            layout: Layout::new(size, style, origin),
+            nodes: HashMap::new(),
+        }
+    }
 
-#[derive(Component, Clone, Copy, Debug, Default, Hash, PartialEq, Eq, PartialOrd)]
-pub struct HexPosition {
-    pub x: i32,
-    pub y: i32,
-}
+    pub fn with_layout(layout: Layout, radius: i32) -> Self {
+        Self {
+            radius,
+            layout,
+            nodes: HashMap::new(),
+        }
+    }
 
-impl Into<Axial> for &HexPosition {
-    fn into(self) -> Axial {
-        Axial {
-            q: self.x,
-            r: self.y,
+    // Insert a value for every hex inside of the radius
+    pub fn fill_with<F>(&mut self, mut value: F)
+    where
+        F: FnMut(&Axial) -> T,
+    {
+        for hex in (Axial { q: 0, r: 0 }).range(self.radius) {
+            let v = value(&hex);
+            self.nodes.insert(hex, v);
         }
     }
-}
 
-impl From<Axial> for HexPosition {
-    fn from(v: Axial) -> Self {
-        Self { x: v.q, y: v.r }
+    pub fn len(&self) -> usize {
+        self.nodes.len()
     }
-}
 
-#[derive(Component, Clone, Copy, Debug, Default, Hash)]
-pub struct HexTexture(pub u32);
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
 
-#[derive(Component, Clone, Copy, Debug, Hash)]
-pub struct HexNodeId(pub Entity);
+    pub fn clear(&mut self) {
+        self.nodes.clear();
+    }
 
-impl Default for HexNodeId {
-    fn default() -> Self {
-        Self(Entity::from_raw(0))
+    pub fn contains(&self, hex: &Axial) -> bool {
+        self.nodes.contains_key(hex)
     }
-}
 
-#[derive(Bundle, Clone, Copy, Debug, Default)]
-pub struct HexBundle {
-    pub position: HexPosition,
-    pub texture: HexTexture,
-    pub node: HexNodeId,
-}
+    pub fn get(&self, hex: &Axial) -> Option<&T> {
+        self.nodes.get(hex)
+    }
 
-pub struct HexMap {
-    pub radius: i32,
-    pub layout: Layout,
-    pub nodes: HashMap<Axial, HexNode>,
-}
+    pub fn get_mut(&mut self, hex: &Axial) -> Option<&mut T> {
+        self.nodes.get_mut(hex)
+    }
 
-#[derive(Clone, Copy)]
-pub struct HexNode {
-    pub key: EventKey,
-    pub value: i32,
-    pub entity: Option<Entity>,
-}
+    pub fn insert(&mut self, hex: Axial, value: T) -> Option<T> {
+        self.nodes.insert(hex, value)
+    }
 
-impl HexMap {
-    pub fn new(size: Vec2, style: orient::Style, origin: Vec2, radius: i32) -> Self {
-        Self {
-            radius: radius,
-            layout: Layout {
-                size: size,
-                style: style.clone(),
-                origin,
-                matrix: Orientation::new(style),
-            },
-            nodes: HashMap::new(),
-        }
+    pub fn remove(&mut self, hex: &Axial) -> Option<T> {
+        self.nodes.remove(hex)
     }
 
-    pub fn spawn_entities(
-        &mut self,
-        color: Color,
-        node_id: Entity,
-        commands: &mut Commands,
-        world_assets: &Res<WorldAssets>,
-    ) -> Vec<Entity> {
-        // Collections setup
-        let mut list = Vec::new();
-
-        // Spawn hex grid entities
-        for q in -self.radius..(self.radius + 1) {
-            let r1 = i32::max(-self.radius, -q - self.radius);
-            let r2 = i32::min(self.radius, -q + self.radius);
-            for r in r1..(r2 + 1) {
-                let hex = Axial { q, r };
-                let name = format!("hex-{}:{}", q, r);
-                let pos = self.layout.center_for(&hex);
-                let bundle = SpriteBundle {
-                    sprite: Sprite {
-                        color: color,
-                        custom_size: Some(Vec2::splat(TILE_SIZE * 2.)),
-                        ..Default::default()
-                    },
-                    texture: world_assets.pointy_hex64_a.clone(),
-                    transform: Transform {
-                        translation: Vec3::new(pos.x, pos.y, 10.0),
-                        ..Default::default()
-                    },
-                    ..default()
-                };
-                let entity = commands
-                    .spawn_bundle(bundle)
-                    .insert_bundle(HexBundle {
-                        position: hex.into(),
-                        texture: HexTexture(0),
-                        node: HexNodeId(node_id),
-                    })
-                    .insert(Name::new(name))
-                    .id();
-
-                self.nodes.insert(
-                    hex,
-                    HexNode {
-                        key: EventKey::Combat,
-                        value: 0,
-                        entity: None,
-                    },
-                );
-                list.push(entity);
-            }
-        }
+    pub fn iter(&self) -> impl Iterator<Item = (&Axial, &T)> {
+        self.nodes.iter()
+    }
 
-        list
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = (&Axial, &mut T)> {
+        self.nodes.iter_mut()
     }
 
-    fn find_nearest(&mut self, target: &Point, start: &Point, list: &Vec<Point>) -> (Point, f32) {
-        let mut nc = start;
-        let mut dc = nc.distance(target);
-        for n in list {
-            let d = n.distance(target);
-            if d < dc {
-                (dc, nc) = (d, n);
-            }
-        }
-        return (nc.clone(), dc);
+    pub fn keys(&self) -> impl Iterator<Item = &Axial> {
+        self.nodes.keys()
     }
 
-    pub fn spawn_points(
-        &mut self,
-        seed: i64,
-        commands: &mut Commands,
-        world_assets: &Res<WorldAssets>,
-    ) {
-        let mut rng = Shift64::new(seed);
-
-        let scale = self.layout.hex_size();
-        let hexes = (i32::abs(-self.radius - self.radius) + 1) as f32;
-
-        // 0.1 Size of the point grid for generation and offset
-        let width = (scale.x * hexes - (34. * 0.25)) as i32;
-        let height = (scale.y * hexes - (34. * 0.25)) as i32;
-        let offset_x = width as f32 * 0.5;
-        let offset_y = height as f32 * 0.5;
-
-        let mut list: Vec<Point> = Vec::new();
-        let mut mapped: Vec<Vec<Option<Point>>> = vec![vec![None; width as usize]; height as usize];
-
-        let rx = width / 4;
-        let ry = height / 4;
-        let root = Point {
-            x: (rng.i32(rx) - (rx / 2)) + (width / 2),
-            y: (rng.i32(ry) - (ry / 2)) + (height / 2),
-        };
-
-        list.push(root);
-        mapped[root.x as usize][root.y as usize] = Some(root);
-
-        for _x in 0..ITERATIONS {
-            let mut rn: i32 = 0;
-            let mut ds: f32 = 0.;
-            let mut na: Point = Point::default();
-            let mut nb: Point = Point::default();
-
-            // 1.  Get random & nearest node (&& distance)
-            // 2.  While min radius > distance between (&& max checks)
-            // 2.1  goto: 1
-            while MAX_RADIUS > ds && MAX_CHECKS > rn {
-                rn += 1;
-                let x = rng.i32(width);
-                let y = rng.i32(height);
-
-                na = Point { x, y };
-                (nb, ds) = self.find_nearest(&na, &root, &list);
-            }
-            // 3.  max radius < distance between
-            // 3.1  change to max distance
-            if MAX_RADIUS < ds {
-                let xc = (na.x - nb.x) as f32 / ds;
-                let yc = (na.y - nb.y) as f32 / ds;
-                // let xi = (AVR_RADIUS*xc + (nb.x) as f32) as i32;
-                // let yi = (AVR_RADIUS*yc + (nb.y) as f32) as i32;
-                na = Point {
-                    x: (AVR_RADIUS * xc + (nb.x as f32)) as i32,
-                    y: (AVR_RADIUS * yc + (nb.y as f32)) as i32,
-                };
-            }
-            // 4.  min radius < distance between
-            // 4.1  check ifnode is inside the map
-            // 4.1.1  add node and link up to graph
-            if self.layout.is_in_range(
-                Vec2 {
-                    x: ((na.x as f32) - offset_x) + self.layout.origin.x,
-                    y: ((na.y as f32) - offset_y) + self.layout.origin.y,
-                },
-                self.radius,
-            ) && MIN_RADIUS <= ds
-            {
-                list.push(na);
-                // if (rn % 2) > 0 {
-                //     na.value = 0.4
-                // } else {
-                //     na.value = 0.2
-                // }
-            }
+    pub fn neighbors(&self, hex: &Axial) -> impl Iterator<Item = (Axial, &T)> {
+        self.select(hex.neighbors().to_vec())
+    }
+
+    // Hexes at exactly n steps from the center
+    pub fn ring(&self, center: &Axial, n: i32) -> impl Iterator<Item = (Axial, &T)> {
+        self.select(center.ring(n))
+    }
+
+    // Rings from the center outwards up to n steps
+    pub fn spiral(&self, center: &Axial, n: i32) -> impl Iterator<Item = (Axial, &T)> {
+        self.select(center.spiral(n))
+    }
+
+    // Hexes inside of n steps, ordered by q then r
+    pub fn range(&self, center: &Axial, n: i32) -> impl Iterator<Item = (Axial, &T)> {
+        self.select(center.range(n))
+    }
+
+    // Move every value to the hex returned by the mapping
+    pub fn remap<F>(&mut self, mapping: F)
+    where
+        F: Fn(&Axial) -> Axial,
+    {
+        let nodes = std::mem::take(&mut self.nodes);
+        for (k, v) in nodes.into_iter() {
+            self.nodes.insert(mapping(&k), v);
         }
+    }
 
-        // Setup the node entity and spawn the grid
-        let name = format!("points-{}:{}", 0, 0);
-        let node_id = commands.spawn().insert(Name::new(name)).id();
-        let mut points = Vec::new();
-
-        // Spawn Points of Intrest
-        let mut energy = TextureAtlasSprite::new(29);
-        energy.color = Color::rgb(0.9, 0.8, 1.0);
-        energy.custom_size = Some(Vec2::splat(TILE_SIZE * 0.5));
-
-        let mut mining = TextureAtlasSprite::new(32);
-        mining.color = Color::rgb(0.9, 0.8, 1.0);
-        mining.custom_size = Some(Vec2::splat(TILE_SIZE * 0.5));
-
-        for pnt in list {
-            let key = rng.i32(256);
-            let value = rng.i32(256);
-            let hex = &self.layout.hex_for(Vec2 {
-                x: ((pnt.x as f32) - offset_x) + self.layout.origin.x,
-                y: ((pnt.y as f32) - offset_y) + self.layout.origin.y,
-            });
-            let pos = self.layout.center_for(hex);
-            if let Some(node) = self.nodes.get_mut(hex) {
-                let entity = commands
-                    .spawn_bundle(SpriteSheetBundle {
-                        sprite: match key > 92 {
-                            true => energy.clone(),
-                            false => mining.clone(),
-                        },
-                        texture_atlas: world_assets.base_space_sheet.clone(),
-                        transform: Transform {
-                            translation: Vec3::new(pos.x, pos.y, 9.0),
-                            ..Default::default()
-                        },
-                        ..Default::default()
-                    })
-                    .id();
-                points.push(entity);
-
-                node.key = match key > 128 {
-                    true => EventKey::Energy,
-                    false => EventKey::Mining,
-                };
-                node.value = value;
-                node.entity = Some(entity);
-            } else {
-                log::error!("Invalid hex: {}", hex)
-            }
+    // Move all values of the other map in to this map, hexes are
+    // matched by their world position so layouts may differ.
+    pub fn merge(&mut self, other: HexMap<T>) {
+        let HexMap { layout, nodes, .. } = other;
+        for (k, v) in nodes.into_iter() {
+            let pos = layout.center_for(&k);
+            let hex = self.layout.hex_for(pos);
+            self.nodes.insert(hex, v);
         }
+    }
 
-        // Finalize hex node and entities as children
-        commands
-            .entity(node_id)
-            .insert_bundle(VisibilityBundle::default())
-            .insert_bundle(TransformBundle::default())
-            .insert(CleanupGrid)
-            .insert(GridRoot)
-            .push_children(&points);
+    pub fn find_path(&self, from: &Axial, to: &Axial, limit: i32) -> Option<HexPath> {
+        HexPath::find(from, to, limit, |hex| match self.nodes.contains_key(hex) {
+            true => Some(1),
+            false => None,
+        })
     }
 
-    pub fn collect(&mut self, other: &mut HexMap) {
-        for (k, v) in other.nodes.iter() {
-            let pos = other.layout.center_for(k);
-            let hex = self.layout.hex_for(pos);
-            self.nodes.insert(hex, v.clone());
+    fn select(&self, keys: Vec<Axial>) -> impl Iterator<Item = (Axial, &T)> {
+        keys.into_iter()
+            .filter_map(move |hex| self.nodes.get(&hex).map(|v| (hex, v)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{orient, Axial, HexMap, Vec2};
+
+    const CENTER: Axial = Axial { q: 1, r: -1 };
+    const EDGE: Axial = Axial { q: 3, r: 0 };
+
+    // Every hex inside of radius 4 holds its own coordinates
+    fn populated() -> HexMap<Axial> {
+        let mut map = HexMap::new(Vec2::splat(10.), orient::Style::Pointy, Vec2::ZERO, 4);
+        map.fill_with(|hex| *hex);
+        map
+    }
+
+    fn on_map(hexes: Vec<Axial>) -> Vec<Axial> {
+        let origin = Axial { q: 0, r: 0 };
+        hexes
+            .into_iter()
+            .filter(|hex| hex.distance(&origin) <= 4)
+            .collect()
+    }
+
+    fn check(found: Vec<(Axial, &Axial)>, expected: Vec<Axial>) {
+        assert!(found.iter().all(|(hex, value)| hex == *value));
+        let found: Vec<Axial> = found.into_iter().map(|(hex, _)| hex).collect();
+        assert_eq!(found, expected);
+    }
+
+    #[test]
+    fn storage_ring() {
+        let map = populated();
+        check(map.ring(&CENTER, 2).collect(), CENTER.ring(2));
+        check(map.ring(&EDGE, 2).collect(), on_map(EDGE.ring(2)));
+        assert!(map.ring(&EDGE, 2).count() < EDGE.ring(2).len());
+    }
+
+    #[test]
+    fn storage_spiral() {
+        let map = populated();
+        check(map.spiral(&CENTER, 2).collect(), CENTER.spiral(2));
+        check(map.spiral(&EDGE, 2).collect(), on_map(EDGE.spiral(2)));
+    }
+
+    #[test]
+    fn storage_range() {
+        let map = populated();
+        check(map.range(&CENTER, 2).collect(), CENTER.range(2));
+        check(map.range(&EDGE, 3).collect(), on_map(EDGE.range(3)));
+        assert_eq!(map.range(&Axial { q: 0, r: 0 }, 4).count(), map.len());
+    }
+
+    #[test]
+    fn storage_remap() {
+        let mut map = populated();
+        let count = map.len();
+        map.remap(|hex| Axial {
+            q: hex.q + 2,
+            r: hex.r - 1,
+        });
+        assert_eq!(map.len(), count);
+        for (hex, value) in map.iter() {
+            assert_eq!(hex.q, value.q + 2);
+            assert_eq!(hex.r, value.r - 1);
+        }
+        assert!(!map.contains(&Axial { q: -4, r: 0 }));
+    }
+
+    #[test]
+    fn storage_merge() {
+        let mut map = populated();
+        let offset = Axial { q: 6, r: -2 };
+
+        // Note: A small map around its own origin, placed on the offset hex
+        let mut other = HexMap::with_layout(map.layout.clone(), 1);
+        other.layout.origin = map.layout.center_for(&offset);
+        other.fill_with(|_| offset);
+        map.merge(other);
+
+        assert_eq!(map.len(), 61 + 7);
+        for hex in offset.range(1) {
+            assert_eq!(map.get(&hex), Some(&offset));
         }
+        assert_eq!(map.get(&CENTER), Some(&CENTER));
     }
 }
@@ -0,0 +1,108 @@
+use super::*;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Axis {
+    Q, // Keeps q, swaps r and s
+    R, // Keeps r, swaps q and s
+    S, // Keeps s, swaps q and r
+}
+
+impl Cuboid {
+    // Rotate around the origin by 60° steps, positive steps follow
+    // the order of `Direction::ALL`, negative steps turn the other way.
+    pub fn rotate(&self, steps: i32) -> Cuboid {
+        let mut v = *self;
+        for _ in 0..steps.rem_euclid(6) {
+            v = Cuboid {
+                q: -v.s,
+                r: -v.q,
+                s: -v.r,
+            };
+        }
+        v
+    }
+
+    // Mirror across the axis through the origin
+    pub fn reflect(&self, axis: Axis) -> Cuboid {
+        let (q, r, s) = (self.q, self.r, self.s);
+        match axis {
+            Axis::Q => Cuboid { q, r: s, s: r },
+            Axis::R => Cuboid { q: s, r, s: q },
+            Axis::S => Cuboid { q: r, r: q, s },
+        }
+    }
+}
+
+impl Axial {
+    pub fn rotate_around(&self, center: &Axial, steps: i32) -> Axial {
+        self.transform_around(center, |v| v.rotate(steps))
+    }
+
+    pub fn reflect_across(&self, center: &Axial, axis: Axis) -> Axial {
+        self.transform_around(center, |v| v.reflect(axis))
+    }
+
+    fn transform_around<F>(&self, center: &Axial, f: F) -> Axial
+    where
+        F: Fn(&Cuboid) -> Cuboid,
+    {
+        let local = Axial {
+            q: self.q - center.q,
+            r: self.r - center.r,
+        };
+        *center + f(&local.cuboid())
+    }
+}
+
+impl<T> HexMap<T> {
+    pub fn rotate(&mut self, center: &Axial, steps: i32) {
+        self.remap(|hex| hex.rotate_around(center, steps));
+    }
+
+    pub fn reflect(&mut self, center: &Axial, axis: Axis) {
+        self.remap(|hex| hex.reflect_across(center, axis));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Axial, Axis, Direction};
+
+    #[test]
+    fn rotate_steps() {
+        let center = Axial { q: 2, r: -1 };
+        for hex in center.range(3) {
+            assert_eq!(hex.rotate_around(&center, 6), hex);
+            assert_eq!(
+                hex.rotate_around(&center, -1),
+                hex.rotate_around(&center, 5)
+            );
+            assert_eq!(
+                hex.rotate_around(&center, 1).distance(&center),
+                hex.distance(&center)
+            );
+        }
+
+        // One step turns each direction in to the next one
+        let origin = Axial { q: 0, r: 0 };
+        for (idx, dir) in Direction::ALL.iter().enumerate() {
+            let next = Direction::ALL[(idx + 1) % 6];
+            assert_eq!(
+                origin.neighbor(*dir).rotate_around(&origin, 1),
+                origin.neighbor(next)
+            );
+        }
+    }
+
+    #[test]
+    fn reflect_axes() {
+        let center = Axial { q: -1, r: 3 };
+        let hex = Axial { q: 1, r: 1 };
+        for axis in [Axis::Q, Axis::R, Axis::S] {
+            let mirror = hex.reflect_across(&center, axis);
+            assert_eq!(mirror.reflect_across(&center, axis), hex);
+            assert_eq!(mirror.distance(&center), hex.distance(&center));
+        }
+        assert_eq!(hex.reflect_across(&center, Axis::Q), Axial { q: 1, r: 3 });
+    }
+}
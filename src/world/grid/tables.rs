@@ -0,0 +1,259 @@
+use bevy::asset::{AssetLoader, LoadContext, LoadedAsset};
+use bevy::reflect::TypeUuid;
+use bevy::utils::BoxedFuture;
+use serde::Deserialize;
+
+use super::*;
+
+#[derive(Clone, Copy, Debug, Default, Deserialize)]
+pub enum EventPayload {
+    #[default]
+    None,
+    Combat(CombatAction),
+    Energy(EnergyAction),
+    Mining(MiningAction),
+    Story,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct EventEntry {
+    pub title: String,
+    pub descr: String,
+    #[serde(default)]
+    pub enter: bool,
+    #[serde(default)]
+    pub weight: u32,
+    #[serde(default)]
+    pub rarity: Rarity,
+    pub action: EventPayload,
+    #[serde(default)]
+    pub choices: Vec<EventChoice>,
+    #[serde(default)]
+    pub pages: Vec<EventPage>,
+}
+
+// One file of events, all entries share the kind of the table
+#[derive(Clone, Debug, Deserialize, TypeUuid)]
+#[uuid = "3f0d3c8a-93b4-4c1e-8f47-2a6f1e9c5b71"]
+pub struct EventTable {
+    pub kind: EventKey,
+    pub events: Vec<EventEntry>,
+}
+
+#[derive(Default)]
+pub struct EventTableLoader;
+
+impl AssetLoader for EventTableLoader {
+    fn load<'a>(
+        &'a self,
+        bytes: &'a [u8],
+        load_context: &'a mut LoadContext,
+    ) -> BoxedFuture<'a, Result<(), anyhow::Error>> {
+        Box::pin(async move {
+            let path = load_context.path().display().to_string();
+            let table = EventTable::from_bytes(&path, bytes).map_err(|e| anyhow::anyhow!(e))?;
+
+            load_context.set_default_asset(LoadedAsset::new(table));
+            Ok(())
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["events.ron"]
+    }
+}
+
+impl EventPayload {
+    pub fn key(&self) -> EventKey {
+        match self {
+            EventPayload::None => EventKey::None,
+            EventPayload::Combat(_) => EventKey::Combat,
+            EventPayload::Energy(_) => EventKey::Energy,
+            EventPayload::Mining(_) => EventKey::Mining,
+            EventPayload::Story => EventKey::Story,
+        }
+    }
+}
+
+impl EventEntry {
+    pub fn info(&self) -> EventInfo<EventPayload> {
+        EventInfo {
+            data: EventData {
+                key: self.action.key(),
+                enter: self.enter,
+                title: self.title.clone(),
+                descr: self.descr.clone(),
+                pages: self.pages(),
+            },
+            action: self.action,
+        }
+    }
+
+    // Note: The opening page is built from the entry, follow-up pages come after it
+    pub fn pages(&self) -> Vec<EventPage> {
+        if self.choices.is_empty() {
+            return Vec::new();
+        }
+        let mut pages = vec![EventPage {
+            title: self.title.clone(),
+            descr: self.descr.clone(),
+            choices: self.choices.clone(),
+        }];
+        pages.extend(self.pages.iter().cloned());
+        pages
+    }
+
+    // Choices of the opening page and of every follow-up page
+    pub fn all_choices(&self) -> impl Iterator<Item = &EventChoice> {
+        let pages = self.pages.iter().flat_map(|page| page.choices.iter());
+        self.choices.iter().chain(pages)
+    }
+
+    pub fn validate_pages(&self) -> Result<(), String> {
+        if self.choices.is_empty() && !self.pages.is_empty() {
+            return Err("follow-up pages without choices".to_string());
+        }
+        let pages = self.pages();
+        for page in pages.iter() {
+            page.validate(pages.len())?;
+        }
+        Ok(())
+    }
+}
+
+impl EventTable {
+    // Parse and check a table, errors start with the path of the file
+    pub fn from_bytes(path: &str, bytes: &[u8]) -> Result<EventTable, String> {
+        let table =
+            ron::de::from_bytes::<EventTable>(bytes).map_err(|e| format!("{}: {}", path, e))?;
+        table.validate().map_err(|e| format!("{}: {}", path, e))?;
+        Ok(table)
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        if self.kind == EventKey::None || self.kind == EventKey::Story {
+            return Err(format!("kind {:?} can not hold random events", self.kind));
+        }
+        if self.events.is_empty() {
+            return Err(format!("{:?} table has no events", self.kind));
+        }
+
+        for (idx, entry) in self.events.iter().enumerate() {
+            let name = format!("entry {} ({:?})", idx, entry.title);
+            if entry.title.trim().is_empty() {
+                return Err(format!("entry {}: title is empty", idx));
+            }
+            if entry.weight == 0 {
+                return Err(format!("{}: weight must be above 0", name));
+            }
+            if entry.action.key() != self.kind {
+                return Err(format!(
+                    "{}: {:?} action in a {:?} table",
+                    name,
+                    entry.action.key(),
+                    self.kind
+                ));
+            }
+            entry
+                .validate_pages()
+                .map_err(|e| format!("{}: {}", name, e))?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{EventKey, EventTable};
+
+    const PATH: &str = "events/test.events.ron";
+
+    fn load(source: &str) -> Result<EventTable, String> {
+        EventTable::from_bytes(PATH, source.as_bytes())
+    }
+
+    fn load_err(source: &str) -> String {
+        let err = load(source).err().unwrap();
+        assert!(err.starts_with(PATH), "{}", err);
+        err
+    }
+
+    #[test]
+    fn tables_shipped() {
+        let tables = [
+            (
+                "combat.events.ron",
+                include_str!("../../../assets/events/combat.events.ron"),
+                EventKey::Combat,
+            ),
+            (
+                "energy.events.ron",
+                include_str!("../../../assets/events/energy.events.ron"),
+                EventKey::Energy,
+            ),
+            (
+                "mining.events.ron",
+                include_str!("../../../assets/events/mining.events.ron"),
+                EventKey::Mining,
+            ),
+        ];
+        for (path, source, kind) in tables {
+            let table = EventTable::from_bytes(path, source.as_bytes()).unwrap();
+            assert_eq!(table.kind, kind);
+            assert!(!table.events.is_empty());
+        }
+    }
+
+    #[test]
+    fn tables_bad_kind() {
+        let err = load_err("(kind: Story, events: [])");
+        assert!(err.contains("Story"), "{}", err);
+        load_err("(kind: Pirates, events: [])");
+
+        let err = load_err("(kind: Combat, events: [])");
+        assert!(err.contains("no events"), "{}", err);
+    }
+
+    #[test]
+    fn tables_bad_weight() {
+        let err = load_err(
+            r#"(kind: Energy, events: [
+                (title: "Star", descr: "", weight: 4, action: Energy((is_large: false, energy: 2))),
+                (title: "Dim Star", descr: "", action: Energy((is_large: false, energy: 1))),
+            ])"#,
+        );
+        assert!(err.contains("entry 1"), "{}", err);
+        assert!(err.contains("Dim Star"), "{}", err);
+
+        load_err(
+            r#"(kind: Energy, events: [
+                (title: "Star", descr: "", weight: -4, action: Energy((is_large: false, energy: 2))),
+            ])"#,
+        );
+    }
+
+    #[test]
+    fn tables_bad_payload() {
+        let err = load_err(
+            r#"(kind: Mining, events: [
+                (title: "Belt", descr: "", weight: 1, action: Energy((is_large: false, energy: 2))),
+            ])"#,
+        );
+        assert!(err.contains("entry 0"), "{}", err);
+        assert!(err.contains("Belt"), "{}", err);
+
+        load_err(
+            r#"(kind: Mining, events: [
+                (title: "Belt", descr: "", weight: 1, action: Mining((material: 2))),
+            ])"#,
+        );
+
+        let err = load_err(
+            r#"(kind: Mining, events: [
+                (title: "Belt", descr: "", weight: 1, action: Mining((is_large: false, material: 2))),
+                (title: " ", descr: "", weight: 1, action: Mining((is_large: false, material: 2))),
+            ])"#,
+        );
+        assert!(err.contains("entry 1"), "{}", err);
+    }
+}
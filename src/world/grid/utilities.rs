@@ -10,11 +10,44 @@ pub struct GridTarget {
 #[derive(Component)]
 pub struct GridTargetHex;
 
-#[derive(Component, Clone, Copy, Default, Debug, Inspectable)]
+#[derive(Component)]
+pub struct GridTargetText;
+
+#[derive(Component)]
+pub struct GridPathHex;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum JumpCheck {
+    #[default]
+    Moving,
+    Ready,
+    OffGrid,
+    OutOfRange,
+    NoEnergy,
+    Locked,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct JumpPreview {
+    pub check: JumpCheck,
+    pub route: Vec<Axial>,
+    pub jumps: Vec<HexJump>,
+    pub cost: u16,
+    pub energy: u16,
+}
+
+#[derive(Component, Clone, Default, Debug, Inspectable)]
 pub struct GridMovement {
     pub cost: u32,
     pub speed: u32,
     pub distance: u32,
+    #[inspectable(ignore)]
+    pub route: Vec<HexJump>,
+}
+
+// Note: Reaching zero energy strands the ship, every jump has to leave one unit
+pub fn can_afford(cost: i32, energy: u16) -> bool {
+    cost < energy as i32
 }
 
 impl GridTarget {
@@ -27,18 +60,9 @@ impl GridTarget {
         window: &Window,
         camera: &Camera,
         transform: &GlobalTransform,
+        layout: &Layout,
     ) {
         if let Some(screen_pos) = window.cursor_position() {
-            let node = HexMap::new(
-                Vec2 {
-                    x: TILE_SIZE,
-                    y: TILE_SIZE,
-                },
-                orient::Style::Pointy,
-                Vec2 { x: 0., y: 0. },
-                12,
-            );
-
             // Convert window position to gpu coordinates
             let window_size = Vec2::new(window.width() as f32, window.height() as f32);
             let ndc_to_world = transform.compute_matrix() * camera.projection_matrix().inverse();
@@ -52,15 +76,117 @@ impl GridTarget {
                 y: world_pos.y,
             };
 
-            let hex = node.layout.hex_for(mouse_pos);
-            self.mouse = node.layout.center_for(&hex);
+            let hex = layout.hex_for(mouse_pos);
+            self.mouse = layout.center_for(&hex);
         }
     }
 }
 
-// impl GridMovement {
-//     pub fn update_current(&mut self, targets: &GridTarget, trasform: &Transform) {
-//         // TODO Implemt Movement target on grid line to active node
-//         // The goal is to update a line from transform to target
-//     }
-// }
+impl GridMovement {
+    pub fn is_idle(&self) -> bool {
+        self.route.is_empty()
+    }
+
+    pub fn set_route(&mut self, route: Vec<HexJump>) {
+        self.route = route;
+    }
+
+    // Energy the jumps left on the route still cost
+    pub fn route_energy(&self, per_hex: i32) -> i32 {
+        self.route.iter().map(|jump| jump.cost * per_hex).sum()
+    }
+
+    // Start the next jump on the route, returns the jump to pay for
+    pub fn update_current(&mut self, target: &mut GridTarget, layout: &Layout) -> Option<HexJump> {
+        if target.moving || self.route.is_empty() {
+            return None;
+        }
+        let jump = self.route.remove(0);
+        target.target = layout.center_for(&jump.hex);
+        target.moving = true;
+        Some(jump)
+    }
+}
+
+impl JumpPreview {
+    pub fn clear(&mut self) {
+        self.check = JumpCheck::Moving;
+        self.route.clear();
+        self.jumps.clear();
+        self.cost = 0;
+    }
+
+    pub fn is_ready(&self) -> bool {
+        self.check == JumpCheck::Ready
+    }
+
+    // Plan the route from the ship to the target hex and check if it is legal
+    pub fn plan(&mut self, grid: &Grid, from: Axial, to: Axial, range: i32, energy: u16) {
+        self.clear();
+        self.energy = energy;
+
+        let path = grid.find_path(&from, &to, range * MAX_ROUTE_JUMPS);
+        if let Some(path) = &path {
+            self.jumps = path.jumps(range);
+            self.route = path.hexes.iter().skip(1).cloned().collect();
+            self.cost = path.energy(ENERGY_COST) as u16;
+        } else {
+            // Note: Show the straight line to explain what is wrong
+            self.route = Axial::line(&from, &to);
+            self.route.retain(|hex| *hex != from);
+        }
+
+        self.check = match path {
+            _ if !grid.on_grid(&to) => JumpCheck::OffGrid,
+            _ if !grid.in_region(&to) => JumpCheck::Locked,
+            None => JumpCheck::OutOfRange,
+            Some(_) if self.jumps.is_empty() => JumpCheck::Moving,
+            Some(_) if !can_afford(self.cost as i32, energy) => JumpCheck::NoEnergy,
+            Some(_) => JumpCheck::Ready,
+        };
+    }
+
+    pub fn label(&self) -> String {
+        match self.check {
+            JumpCheck::Moving => String::new(),
+            JumpCheck::Ready => format!("-{} energy ({} left)", self.cost, self.energy - self.cost),
+            JumpCheck::OffGrid => "off grid".to_string(),
+            JumpCheck::OutOfRange => "out of range".to_string(),
+            JumpCheck::NoEnergy => format!("need {} energy ({} left)", self.cost, self.energy),
+            JumpCheck::Locked => "not charted yet".to_string(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{can_afford, Axial, GridMovement, HexJump, JumpCheck, JumpPreview, ENERGY_COST};
+
+    #[test]
+    fn jump_energy_boundary() {
+        assert!(can_afford(11, 12));
+        assert!(!can_afford(12, 12));
+        assert!(!can_afford(13, 12));
+
+        let jump = |q: i32, cost: i32| HexJump {
+            hex: Axial { q, r: 0 },
+            cost,
+        };
+        let mut movement = GridMovement::default();
+        movement.set_route(vec![jump(2, 2), jump(3, 1)]);
+        assert_eq!(movement.route_energy(ENERGY_COST), 12);
+        assert!(!can_afford(movement.route_energy(ENERGY_COST), 12));
+        assert!(can_afford(movement.route_energy(ENERGY_COST), 13));
+    }
+
+    #[test]
+    fn jump_preview_label() {
+        let preview = JumpPreview {
+            check: JumpCheck::NoEnergy,
+            cost: 12,
+            energy: 12,
+            ..Default::default()
+        };
+        assert_eq!(preview.label(), "need 12 energy (12 left)");
+    }
+}
@@ -0,0 +1,208 @@
+use serde::Deserialize;
+
+use super::*;
+
+#[derive(Clone, Copy, Debug, Default, Hash, PartialEq, Eq, Deserialize)]
+pub enum Rarity {
+    #[default]
+    Common,
+    Uncommon,
+    Rare,
+    Legendary,
+}
+
+// Shifts the odds towards rarer entries, both values go from 0 to 1
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct RollModifier {
+    pub distance: f32,   // From the galaxy center to its edge
+    pub difficulty: f32, // From a fresh run to a late one
+}
+
+#[derive(Clone, Debug)]
+pub struct WeightedEntry<T> {
+    pub value: T,
+    pub weight: u32,
+    pub rarity: Rarity,
+}
+
+#[derive(Clone, Debug)]
+pub struct WeightedTable<T> {
+    entries: Vec<WeightedEntry<T>>,
+}
+
+impl Rarity {
+    // Base multiplier of the tier
+    pub fn odds(&self) -> f32 {
+        match self {
+            Rarity::Common => 1.0,
+            Rarity::Uncommon => 0.5,
+            Rarity::Rare => 0.2,
+            Rarity::Legendary => 0.05,
+        }
+    }
+
+    // How much the tier gains from the roll modifier
+    pub fn boost(&self) -> f32 {
+        match self {
+            Rarity::Common => 0.0,
+            Rarity::Uncommon => 0.5,
+            Rarity::Rare => 1.5,
+            Rarity::Legendary => 3.0,
+        }
+    }
+}
+
+impl RollModifier {
+    pub fn new(distance: f32, difficulty: f32) -> Self {
+        Self {
+            distance: distance.clamp(0., 1.),
+            difficulty: difficulty.clamp(0., 1.),
+        }
+    }
+
+    pub fn scale(&self, rarity: Rarity) -> f32 {
+        1.0 + (self.distance + self.difficulty) * rarity.boost()
+    }
+}
+
+impl<T> Default for WeightedTable<T> {
+    fn default() -> Self {
+        Self {
+            entries: Vec::new(),
+        }
+    }
+}
+
+impl<T> WeightedTable<T> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn push(&mut self, value: T, weight: u32, rarity: Rarity) {
+        self.entries.push(WeightedEntry {
+            value,
+            weight,
+            rarity,
+        });
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &WeightedEntry<T>> {
+        self.entries.iter()
+    }
+
+    // Note: Scaled by 1000 so the fractional tier odds survive as integers
+    pub fn weights(&self, modifier: &RollModifier) -> Vec<u32> {
+        self.entries
+            .iter()
+            .map(|e| {
+                let w = e.weight as f32 * e.rarity.odds() * modifier.scale(e.rarity);
+                (w * 1000.).round() as u32
+            })
+            .collect()
+    }
+
+    // Share of every entry in the rolls, sums up to 1 unless all weights are 0
+    pub fn chances(&self, modifier: &RollModifier) -> Vec<f32> {
+        let weights = self.weights(modifier);
+        let total = weights.iter().sum::<u32>() as f32;
+        weights
+            .iter()
+            .map(|w| match total > 0. {
+                true => *w as f32 / total,
+                false => 0.,
+            })
+            .collect()
+    }
+
+    pub fn roll(&self, rng: &mut Shift64, modifier: &RollModifier) -> Option<&T> {
+        rng.weighted(&self.weights(modifier))
+            .map(|idx| &self.entries[idx].value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Rarity, RollModifier, Shift64, WeightedTable};
+
+    const ROLLS: usize = 20_000;
+
+    fn table() -> WeightedTable<&'static str> {
+        let mut table = WeightedTable::new();
+        table.push("empty", 60, Rarity::Common);
+        table.push("small", 30, Rarity::Uncommon);
+        table.push("big", 30, Rarity::Rare);
+        table.push("relic", 20, Rarity::Legendary);
+        table
+    }
+
+    fn shares(table: &WeightedTable<&'static str>, modifier: &RollModifier) -> Vec<f32> {
+        let mut rng = Shift64::new(1234);
+        let mut counts = vec![0usize; table.len()];
+        for _ in 0..ROLLS {
+            let value = table.roll(&mut rng, modifier).unwrap();
+            let idx = table.iter().position(|e| e.value == *value).unwrap();
+            counts[idx] += 1;
+        }
+        counts.iter().map(|c| *c as f32 / ROLLS as f32).collect()
+    }
+
+    #[test]
+    fn weighted_chances() {
+        let table = table();
+        let chances = table.chances(&RollModifier::default());
+
+        // 60 : 15 : 6 : 1 after the tier odds
+        let expected = [60. / 82., 15. / 82., 6. / 82., 1. / 82.];
+        for (c, e) in chances.iter().zip(expected) {
+            assert!((c - e).abs() < 0.0005, "{} != {}", c, e);
+        }
+
+        let edge = table.chances(&RollModifier::new(1., 1.));
+        assert!(edge[0] < chances[0]);
+        assert!(edge[3] > chances[3] * 4.);
+        assert!((edge.iter().sum::<f32>() - 1.).abs() < 0.0001);
+    }
+
+    #[test]
+    fn weighted_rolls() {
+        let table = table();
+        for modifier in [
+            RollModifier::default(),
+            RollModifier::new(0.5, 0.),
+            RollModifier::new(1., 1.),
+        ] {
+            let chances = table.chances(&modifier);
+            for (share, chance) in shares(&table, &modifier).iter().zip(chances) {
+                assert!((share - chance).abs() < 0.01, "{} != {}", share, chance);
+            }
+        }
+
+        // Fixed seed, fixed rolls, the edge of the galaxy rolls rarer
+        let roll = |modifier: RollModifier| {
+            let mut rng = Shift64::new(99);
+            (0..8)
+                .map(|_| *table.roll(&mut rng, &modifier).unwrap())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            roll(RollModifier::default()),
+            ["empty", "empty", "empty", "empty", "empty", "empty", "small", "empty"]
+        );
+        assert_eq!(
+            roll(RollModifier::new(1., 1.)),
+            ["empty", "small", "small", "empty", "empty", "empty", "big", "empty"]
+        );
+
+        let mut rng = Shift64::new(99);
+        let empty: WeightedTable<&str> = WeightedTable::new();
+        assert_eq!(empty.roll(&mut rng, &RollModifier::default()), None);
+    }
+}
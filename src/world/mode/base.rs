@@ -4,6 +4,9 @@ use bevy::ui::FocusPolicy;
 
 const FAILED_TO_SET_STATE: &str = "Failed to set game state";
 
+const SEED_LENGTH: usize = 16;
+const SEED_RANDOM: &str = "abcdefghjkmnpqrstuvwxyz23456789";
+
 #[derive(Clone, Copy, Default)]
 pub enum ShipKey {
     #[default]
@@ -34,6 +37,16 @@ pub struct ShipInfo {
     pub energy: u16,
 }
 
+// The seed of the current run, the same text always rolls the same galaxy
+pub struct RunSeed {
+    pub text: String,
+    pub value: i64,
+    pub custom: bool,
+}
+
+#[derive(Component)]
+pub struct SeedText;
+
 pub struct BaseModePlugin;
 
 #[derive(Component)]
@@ -51,13 +64,63 @@ impl Plugin for BaseModePlugin {
             energy: 12,
         });
 
+        app.insert_resource(RunSeed::random());
+
         // TODO Generate the map data while in this state, remove transition delay
+        app.add_system_set(
+            SystemSet::on_enter(base_grid).with_system(enter_seed_input.label("seed-input")),
+        );
         app.add_system_set(SystemSet::on_update(base_grid).with_system(button_update));
+        app.add_system_set(SystemSet::on_update(base_grid).with_system(seed_input));
+    }
+}
+
+fn enter_seed_input(mut seed: ResMut<RunSeed>) {
+    // Note: Typed seeds are kept so a run can be replayed after game over
+    if !seed.custom {
+        *seed = RunSeed::random();
+    }
+}
+
+fn seed_input(
+    keys: Res<Input<KeyCode>>,
+    mut seed: ResMut<RunSeed>,
+    mut chars: EventReader<ReceivedCharacter>,
+    mut text_query: Query<&mut Text, With<SeedText>>,
+) {
+    let erase = keys.just_pressed(KeyCode::Back);
+    let typed: Vec<char> = chars
+        .iter()
+        .map(|ev| ev.char.to_ascii_lowercase())
+        .filter(|c| c.is_ascii_alphanumeric() || *c == '-')
+        .collect();
+
+    // Note: The random seed is only a suggestion, the first key replaces all of it
+    let mut text = seed.text.clone();
+    if !seed.custom && (erase || !typed.is_empty()) {
+        text.clear();
+    } else if erase {
+        text.pop();
+    }
+    for c in typed {
+        if text.len() < SEED_LENGTH {
+            text.push(c);
+        }
+    }
+
+    if text != seed.text {
+        seed.text = text;
+        seed.custom = true;
+        for mut text in text_query.iter_mut() {
+            text.sections[0].value = seed.label();
+        }
     }
 }
 
 fn button_update(
     mut info: ResMut<ShipInfo>,
+    mut seed: ResMut<RunSeed>,
+    mut streams: ResMut<RngStreams>,
     mut state: ResMut<State<AppState>>,
     mut buttons: ResMut<Input<MouseButton>>,
     mut button_query: Query<
@@ -71,7 +134,7 @@ fn button_update(
                 buttons.clear();
                 transform.scale *= 1.05;
                 *color = gui::PRESSED_BUTTON.into();
-                handle_btn_update_click(ship, &mut info, &mut state);
+                handle_btn_update_click(ship, &mut info, &mut seed, &mut streams, &mut state);
             }
             Interaction::Hovered => {
                 transform.scale *= 0.95;
@@ -88,10 +151,20 @@ fn button_update(
 fn handle_btn_update_click(
     bnt: &ShipInfo,
     info: &mut ResMut<ShipInfo>,
+    seed: &mut ResMut<RunSeed>,
+    streams: &mut ResMut<RngStreams>,
     state: &mut ResMut<State<AppState>>,
 ) {
     log::info!("SelectKey::clicked: Ship Selection");
 
+    // An empty field falls back to a random seed
+    if seed.text.is_empty() {
+        **seed = RunSeed::random();
+    }
+    seed.value = get_seed(seed.text.clone());
+    **streams = RngStreams::new(seed.value);
+    log::info!("run seed: {}", seed.text);
+
     info.key = bnt.key;
     info.jump = bnt.jump;
     info.speed = bnt.speed;
@@ -165,6 +238,48 @@ pub(crate) fn base_mode_select(
     root
 }
 
+pub(crate) fn base_mode_seed(
+    commands: &mut Commands,
+    app_assets: &Res<AppAssets>,
+    seed: &RunSeed,
+) -> Entity {
+    commands
+        .spawn_bundle(NodeBundle {
+            style: Style {
+                size: Size::new(Val::Percent(50.0), Val::Px(65.0)),
+                flex_direction: FlexDirection::Row,
+                justify_content: JustifyContent::Center,
+                align_items: AlignItems::Center,
+                ..default()
+            },
+            color: Color::NONE.into(),
+            ..default()
+        })
+        .insert(Name::new("seed-input"))
+        .insert(CleanupBaseMode)
+        .with_children(|parent| {
+            parent.spawn_bundle(TextBundle::from_section(
+                "seed: ",
+                TextStyle {
+                    font: app_assets.gui_font.clone(),
+                    font_size: 32.0,
+                    color: gui::TEXT_BUTTON,
+                },
+            ));
+            parent
+                .spawn_bundle(TextBundle::from_section(
+                    seed.label(),
+                    TextStyle {
+                        font: app_assets.gui_font.clone(),
+                        font_size: 32.0,
+                        color: gui::TEXT_BUTTON,
+                    },
+                ))
+                .insert(SeedText);
+        })
+        .id()
+}
+
 fn spawn_ship_stats(
     commands: &mut Commands,
     info: ShipInfo,
@@ -369,3 +484,32 @@ impl ShipInfo {
         }
     }
 }
+
+impl RunSeed {
+    pub fn new(text: String) -> Self {
+        Self {
+            value: get_seed(text.clone()),
+            text,
+            custom: true,
+        }
+    }
+
+    // Note: Random seeds are short words so they are easy to share
+    pub fn random() -> Self {
+        let chars = SEED_RANDOM.as_bytes();
+        let text = (0..6)
+            .map(|_| chars[rand::random::<usize>() % chars.len()] as char)
+            .collect::<String>();
+        Self {
+            custom: false,
+            ..Self::new(text)
+        }
+    }
+
+    pub fn label(&self) -> String {
+        match self.text.is_empty() {
+            true => "random".to_string(),
+            false => self.text.clone(),
+        }
+    }
+}
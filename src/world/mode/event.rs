@@ -2,23 +2,75 @@ use super::*;
 
 use crate::gui::gamehud::*;
 
+pub mod ai;
+pub use ai::*;
+
+pub mod weapon;
+pub use weapon::*;
+
+// Fragments drift to the ship inside this range and are picked up on contact
+const FRAGMENT_RANGE: f32 = TILE_SIZE * 1.5;
+const FRAGMENT_PICKUP: f32 = TILE_SIZE * 0.3;
+
+// Seconds in the safe band to drain a star, heat hits the hull every tick
+const HARVEST_TIME: f32 = 8.0;
+const HEAT_TICK: f32 = 0.5;
+
+// Pooled projectile, hidden while it is free
 #[derive(Component, Default)]
 pub struct Laser {
     timeout: Timer,
     direction: Vec3,
+    speed: f32,
+    damage: i32,
+    homing: f32,
+    from_player: bool,
 }
 
-#[derive(Component, Default)]
+#[derive(Component)]
 pub struct Enemy {
     pub hp: i32,
     pub timeout: Timer,
+    pub rng: Shift64,
 }
 
-#[derive(Component, Default)]
-pub struct PlayerLaser;
+// Hit circle for lasers, checked through the spatial hash
+#[derive(Component)]
+pub struct Collider {
+    pub radius: f32,
+}
+
+#[derive(Component)]
+pub struct Asteroid {
+    pub hp: i32,
+    pub material: u16,
+    pub spin: f32,
+    pub rng: Shift64,
+}
 
+#[derive(Component)]
+pub struct Fragment {
+    pub material: u16,
+    pub timeout: Timer,
+    pub direction: Vec3,
+}
+
+#[derive(Component)]
+pub struct Star {
+    pub energy: f32,  // Energy left to harvest
+    pub rate: f32,    // Energy per second inside the band
+    pub pending: f32, // Harvested but not yet a whole unit
+    pub inner: f32,   // Closer than this the hull heats up
+    pub outer: f32,   // Further than this nothing is collected
+    pub heat: Timer,
+}
+
+#[derive(Component)]
+pub struct StarBand;
+
+// Marks a running mining encounter, it is done once the field is empty
 #[derive(Component, Default)]
-pub struct EnemyLaser;
+pub struct MiningField;
 
 #[derive(Component, Default)]
 pub struct CleanupEvent;
@@ -28,6 +80,13 @@ pub struct EventModePlugin;
 pub struct EventDone {
     count: u16,
 }
+
+// Note: Player lasers hit enemies and asteroids, enemy lasers only hit the player
+pub struct LaserHit {
+    pub laser: Entity,
+    pub target: Entity,
+    pub from_player: bool,
+}
 // TODO Energy consumption for lasers
 // TODO Energy consumption for movement
 
@@ -37,14 +96,59 @@ impl Plugin for EventModePlugin {
         let event_mode = AppState::GamePlay(GameMode::EventGrid);
 
         app.add_event::<EventDone>();
+        app.add_event::<LaserHit>();
+        app.add_event::<FireEvent>();
+        app.insert_resource(EncounterRng {
+            rng: Shift64::new(0),
+        });
+
+        register_event_kinds(
+            &mut app
+                .world
+                .get_resource_or_insert_with(EventRegistry::default),
+        );
 
         app.add_system_set(SystemSet::on_exit(game_over).with_system(exit_event_gameplay));
 
         app.add_system_set(SystemSet::on_exit(event_mode).with_system(exit_event_gameplay));
         app.add_system_set(SystemSet::on_enter(event_mode).with_system(enter_event_gameplay));
+        app.add_system_set(SystemSet::on_enter(event_mode).with_system(spawn_projectile_pool));
 
-        app.add_system_set(SystemSet::on_update(event_mode).with_system(enemy_fire_system));
-        app.add_system_set(SystemSet::on_update(event_mode).with_system(player_fire_system));
+        app.add_system_set(
+            SystemSet::on_update(event_mode)
+                .with_system(enemy_fire_system)
+                .label("weapon-fire"),
+        );
+        app.add_system_set(
+            SystemSet::on_update(event_mode)
+                .with_system(enemy_ai_system)
+                .after("player-move")
+                .after("ship-hits"),
+        );
+        app.add_system_set(
+            SystemSet::on_update(event_mode)
+                .with_system(select_weapon)
+                .label("weapon-select"),
+        );
+        app.add_system_set(
+            SystemSet::on_update(event_mode)
+                .with_system(player_fire_system)
+                .after("weapon-select")
+                .label("weapon-fire"),
+        );
+        app.add_system_set(
+            SystemSet::on_update(event_mode)
+                .with_system(emit_projectiles)
+                .after("weapon-fire")
+                .before("laser-move")
+                .label("laser-emit"),
+        );
+        app.add_system_set(
+            SystemSet::on_update(event_mode)
+                .with_system(lasers_homing)
+                .after("laser-emit")
+                .before("laser-move"),
+        );
 
         app.add_system_set(
             SystemSet::on_update(event_mode)
@@ -52,14 +156,43 @@ impl Plugin for EventModePlugin {
                 .after("player-move")
                 .label("laser-move"),
         );
+        app.add_system_set(
+            SystemSet::on_update(event_mode)
+                .with_system(detect_laser_hits)
+                .after("laser-move")
+                .label("laser-hits"),
+        );
         app.add_system_set(
             SystemSet::on_update(event_mode)
                 .with_system(lasers_enemy_hits)
-                .after("laser-move"),
+                .after("laser-hits"),
         );
         app.add_system_set(
             SystemSet::on_update(event_mode)
                 .with_system(lasers_player_hits)
+                .after("laser-hits")
+                .label("ship-hits"),
+        );
+
+        app.add_system_set(
+            SystemSet::on_update(event_mode)
+                .with_system(lasers_asteroid_hits)
+                .after("laser-hits"),
+        );
+        app.add_system_set(
+            SystemSet::on_update(event_mode)
+                .with_system(fragments_movement)
+                .after("player-move"),
+        );
+        app.add_system_set(SystemSet::on_update(event_mode).with_system(asteroids_spin));
+        app.add_system_set(
+            SystemSet::on_update(event_mode)
+                .with_system(harvest_star)
+                .after("player-move"),
+        );
+        app.add_system_set(
+            SystemSet::on_update(event_mode)
+                .with_system(on_mining_done)
                 .after("laser-move"),
         );
 
@@ -67,11 +200,72 @@ impl Plugin for EventModePlugin {
 
         app.add_system_set(SystemSet::on_update(event_mode).with_system(update_health_text));
         app.add_system_set(SystemSet::on_update(event_mode).with_system(update_energy_text));
+        app.add_system_set(SystemSet::on_update(event_mode).with_system(update_cargo_text));
+        app.add_system_set(SystemSet::on_update(event_mode).with_system(update_weapon_text));
+    }
+}
+
+// Note: Every event kind with its buttons and behaviour, add new kinds here
+pub(crate) fn register_event_kinds(registry: &mut EventRegistry) {
+    registry.register(
+        EventKey::Combat,
+        EventAction::new("attack", "escape"),
+        Some(enter_combat_event),
+        None,
+    );
+    registry.register(
+        EventKey::Energy,
+        EventAction::new("collect", "harvest"),
+        Some(enter_energy_event),
+        Some(resolve_energy_event),
+    );
+    registry.register(
+        EventKey::Mining,
+        EventAction::new("mine", "gather"),
+        Some(enter_mining_event),
+        Some(resolve_mining_event),
+    );
+    registry.register(EventKey::Story, EventAction::new("--", "leave"), None, None);
+}
+
+fn enter_combat_event(ctx: &mut EnterContext, payload: &EventPayload) {
+    if let EventPayload::Combat(action) = payload {
+        ctx.player.targets = action.enemies;
+        spawn_combat_event(ctx.commands, ctx.rng, ctx.assets, action, ctx.center);
+    }
+}
+
+fn enter_energy_event(ctx: &mut EnterContext, payload: &EventPayload) {
+    if let EventPayload::Energy(action) = payload {
+        ctx.player.targets = 1;
+        spawn_energy_event(ctx.commands, ctx.rng, ctx.assets, action, ctx.center);
+    }
+}
+
+fn enter_mining_event(ctx: &mut EnterContext, payload: &EventPayload) {
+    if let EventPayload::Mining(action) = payload {
+        ctx.player.targets =
+            spawn_mining_event(ctx.commands, ctx.rng, ctx.assets, action, ctx.center);
+    }
+}
+
+fn resolve_energy_event(ctx: &mut ResolveContext, payload: &EventPayload) {
+    if let EventPayload::Energy(action) = payload {
+        let energy = &mut *ctx.energy;
+        energy.value = (energy.value + action.energy).clamp(0, energy.max);
+    }
+}
+
+fn resolve_mining_event(ctx: &mut ResolveContext, payload: &EventPayload) {
+    if let EventPayload::Mining(action) = payload {
+        let health = &mut *ctx.health;
+        health.value = (health.value + action.material).clamp(0, health.max);
     }
 }
 
 fn on_event_done(
     mut state: ResMut<State<AppState>>,
+    mut story: ResMut<StoryState>,
     mut event_done: EventReader<EventDone>,
     mut player_query: Query<&mut Player>,
 ) {
@@ -81,6 +275,7 @@ fn on_event_done(
     }
     for ev in event_done.iter() {
         player.total = player.total + ev.count;
+        story.progress = story.progress + ev.count;
         state
             .pop()
             .unwrap_or_else(|error| log::error!("on_event_done: {}", error));
@@ -115,6 +310,20 @@ fn update_energy_text(
     let value = stat.value.to_string();
     text.sections[0].value = format!("{value}/{max}");
 }
+fn update_cargo_text(
+    stats_query: Query<&CargoRecource, With<Player>>,
+    mut text_query: Query<&mut Text, With<CargoText>>,
+) {
+    let stat = stats_query.single();
+    let mut text = text_query.single_mut();
+
+    if text.sections.len() == 0 {
+        return;
+    }
+    let max = stat.max.to_string();
+    let value = stat.value.to_string();
+    text.sections[0].value = format!("{value}/{max}");
+}
 
 fn exit_event_gameplay(mut commands: Commands, query: Query<Entity, With<CleanupEvent>>) {
     log::info!("exit_event_gameplay");
@@ -125,46 +334,36 @@ fn exit_event_gameplay(mut commands: Commands, query: Query<Entity, With<Cleanup
 
 fn enter_event_gameplay(
     mut commands: Commands,
-    mut grid: ResMut<Grid>,
+    grid: Res<Grid>,
+    registry: Res<EventRegistry>,
+    mut streams: ResMut<RngStreams>,
     world_assets: Res<WorldAssets>,
     mut player_query: Query<(&mut Player, &Transform)>,
 ) {
     log::info!("enter_event_gameplay");
     let (mut player, transform) = player_query.single_mut();
-    let mut rng = Shift64::new(rand::random());
-    match grid.key {
-        EventKey::None => (),
-        EventKey::Combat => {
-            let event = &grid.get_event_combat();
-            player.targets = event.enemies;
-            spawn_combat_event(
-                &mut commands,
-                &mut rng,
-                &world_assets,
-                &event,
-                Vec2 {
-                    x: transform.translation.x,
-                    y: transform.translation.y,
-                },
-            )
-        }
-        EventKey::Energy => (),
-        EventKey::Mining => {
-            let event = &grid.get_event_mining();
-            player.targets = event.material;
-            spawn_mining_event(&mut commands, &grid.get_event_mining())
-        }
-    }
+    let mut rng = streams.combat.fork();
+    let mut ctx = EnterContext {
+        commands: &mut commands,
+        rng: &mut rng,
+        assets: &world_assets,
+        player: &mut *player,
+        center: Vec2 {
+            x: transform.translation.x,
+            y: transform.translation.y,
+        },
+    };
+    registry.enter(&mut ctx, &grid.get_event_action());
+    commands.insert_resource(EncounterRng { rng: rng.fork() });
 }
 
 fn spawn_combat_event(
     commands: &mut Commands,
     rng: &mut Shift64,
-    assets: &Res<WorldAssets>,
+    assets: &WorldAssets,
     action: &CombatAction,
     center: Vec2,
 ) {
-    let mut rng = Shift64::new(rand::random());
     let mut small_ship = TextureAtlasSprite::new(12);
     small_ship.color = Color::rgb(0.9, 0.7, 0.9);
     small_ship.custom_size = Some(Vec2::splat(TILE_SIZE * 0.5));
@@ -176,24 +375,22 @@ fn spawn_combat_event(
     let mut enemies = action.enemies;
     if action.is_large {
         enemies = enemies - 1;
-        let position = Vec2 {
-            x: center.x + rng.f32(TILE_SIZE * 12.) - (TILE_SIZE * 6.),
-            y: center.y + rng.f32(TILE_SIZE * 12.) - (TILE_SIZE * 6.),
-        };
+        let position = scatter(rng, center);
         spawn_combat_event_big(
             commands,
+            rng.fork(),
+            action.mix,
             position,
             assets.base_space_sheet.clone(),
             big_ship.clone(),
         );
     }
     for _ in 0..enemies {
-        let position = Vec2 {
-            x: center.x + rng.f32(TILE_SIZE * 12.) - (TILE_SIZE * 6.),
-            y: center.y + rng.f32(TILE_SIZE * 12.) - (TILE_SIZE * 6.),
-        };
+        let position = scatter(rng, center);
         spawn_combat_event_small(
             commands,
+            rng.fork(),
+            action.mix,
             (0.01 * rng.f32(240.)),
             position,
             assets.base_space_sheet.clone(),
@@ -204,6 +401,8 @@ fn spawn_combat_event(
 
 fn spawn_combat_event_small(
     commands: &mut Commands,
+    mut rng: Shift64,
+    mix: CombatMix,
     time: f32,
     position: Vec2,
     atlas: Handle<TextureAtlas>,
@@ -219,15 +418,23 @@ fn spawn_combat_event_small(
             },
             ..Default::default()
         })
+        .insert(EnemyAi::new(&mut rng, Archetype::Small, mix))
+        .insert(Weapon::enemy_laser())
+        .insert(Collider {
+            radius: TILE_SIZE * 0.25,
+        })
         .insert(Enemy {
             hp: 4,
             timeout: Timer::from_seconds(1.2 + time, false),
+            rng,
         })
         .insert(CleanupEvent);
 }
 
 fn spawn_combat_event_big(
     commands: &mut Commands,
+    mut rng: Shift64,
+    mix: CombatMix,
     position: Vec2,
     atlas: Handle<TextureAtlas>,
     sprite: TextureAtlasSprite,
@@ -242,16 +449,184 @@ fn spawn_combat_event_big(
             },
             ..Default::default()
         })
+        .insert(EnemyAi::new(&mut rng, Archetype::Large, mix))
+        .insert(Weapon::heavy_laser())
+        .insert(Collider {
+            radius: TILE_SIZE * 0.4,
+        })
         .insert(Enemy {
             hp: 8,
             timeout: Timer::from_seconds(1.2, false),
+            rng,
         })
         .insert(CleanupEvent);
 }
 
-fn spawn_mining_event(commands: &mut Commands, action: &MiningAction) {
-    // TODO Implement
-    log::warn!("not implemented")
+fn spawn_energy_event(
+    commands: &mut Commands,
+    rng: &mut Shift64,
+    assets: &WorldAssets,
+    action: &EnergyAction,
+    center: Vec2,
+) {
+    // Note: Large stars are hotter but pay out twice, small ones half again
+    let (energy, inner, band) = match action.is_large {
+        true => (action.energy as f32 * 2.0, TILE_SIZE * 1.6, TILE_SIZE * 2.0),
+        false => (action.energy as f32 * 1.5, TILE_SIZE * 1.0, TILE_SIZE * 1.5),
+    };
+    let outer = inner + band;
+
+    // Keep the ship out of the heat when the encounter starts
+    let angle = rng.f32(std::f32::consts::TAU);
+    let offset = Vec2::new(angle.sin(), angle.cos()) * (outer + TILE_SIZE);
+    let position = center + offset;
+
+    let mut core = TextureAtlasSprite::new(29);
+    core.color = Color::rgb(1.0, 0.85, 0.5);
+    core.custom_size = Some(Vec2::splat(inner * 2.));
+
+    let mut glow = TextureAtlasSprite::new(29);
+    glow.color = Color::rgba(1.0, 0.7, 0.3, 0.25);
+    glow.custom_size = Some(Vec2::splat(outer * 2.));
+
+    commands
+        .spawn_bundle(SpriteSheetBundle {
+            sprite: core,
+            texture_atlas: assets.base_space_sheet.clone(),
+            transform: Transform::from_xyz(position.x, position.y, 7.0),
+            ..Default::default()
+        })
+        .insert(Name::new("harvest-star"))
+        .insert(Star {
+            energy,
+            rate: energy / HARVEST_TIME,
+            pending: 0.,
+            inner,
+            outer,
+            heat: Timer::from_seconds(HEAT_TICK, true),
+        })
+        .insert(CleanupEvent)
+        .with_children(|parent| {
+            parent
+                .spawn_bundle(SpriteSheetBundle {
+                    sprite: glow,
+                    texture_atlas: assets.base_space_sheet.clone(),
+                    transform: Transform::from_xyz(0., 0., -1.0),
+                    ..Default::default()
+                })
+                .insert(StarBand);
+        });
+}
+
+// Spawns the astroid field, returns the number of rocks to break
+fn spawn_mining_event(
+    commands: &mut Commands,
+    rng: &mut Shift64,
+    assets: &WorldAssets,
+    action: &MiningAction,
+    center: Vec2,
+) -> u16 {
+    // Note: Every rock holds a share of the material, a large belt adds one big rock
+    let mut material = action.material;
+    let mut rocks = u16::max(action.material / 2, 1);
+    if action.is_large {
+        let share = material / 2;
+        material = material - share;
+        let position = scatter(rng, center);
+        spawn_asteroid(commands, rng, assets, position, 10, share, 1.2);
+    }
+    for idx in 0..rocks {
+        let share = material / (rocks - idx);
+        material = material - share;
+        let position = scatter(rng, center);
+        spawn_asteroid(commands, rng, assets, position, 3, share, 0.6);
+    }
+    if action.is_large {
+        rocks = rocks + 1;
+    }
+
+    commands
+        .spawn()
+        .insert(Name::new("mining-field"))
+        .insert(MiningField)
+        .insert(CleanupEvent);
+    rocks
+}
+
+fn scatter(rng: &mut Shift64, center: Vec2) -> Vec2 {
+    Vec2 {
+        x: center.x + rng.f32(TILE_SIZE * 12.) - (TILE_SIZE * 6.),
+        y: center.y + rng.f32(TILE_SIZE * 12.) - (TILE_SIZE * 6.),
+    }
+}
+
+fn spawn_asteroid(
+    commands: &mut Commands,
+    rng: &mut Shift64,
+    assets: &WorldAssets,
+    position: Vec2,
+    hp: i32,
+    material: u16,
+    size: f32,
+) {
+    let mut sprite = TextureAtlasSprite::new(32);
+    sprite.color = Color::rgb(0.75, 0.7, 0.65);
+    sprite.custom_size = Some(Vec2::splat(TILE_SIZE * size));
+    commands
+        .spawn_bundle(SpriteSheetBundle {
+            sprite,
+            texture_atlas: assets.base_space_sheet.clone(),
+            transform: Transform {
+                rotation: Quat::from_rotation_z(rng.f32(std::f32::consts::TAU)),
+                translation: Vec3::new(position.x, position.y, 8.0),
+                ..Default::default()
+            },
+            ..Default::default()
+        })
+        .insert(Asteroid {
+            hp,
+            material,
+            spin: rng.f32(1.) - 0.5,
+            rng: rng.fork(),
+        })
+        .insert(Collider {
+            radius: TILE_SIZE * size * 0.5,
+        })
+        .insert(CleanupEvent);
+}
+
+fn spawn_fragments(
+    commands: &mut Commands,
+    asteroid: &mut Asteroid,
+    position: Vec3,
+    atlas: Handle<TextureAtlas>,
+) {
+    let mut sprite = TextureAtlasSprite::new(32);
+    sprite.color = Color::rgb(0.9, 0.8, 0.5);
+    sprite.custom_size = Some(Vec2::splat(TILE_SIZE * 0.2));
+
+    // Note: Up to three pieces, the first one takes what does not split evenly
+    let pieces = u16::min(u16::max(asteroid.material, 1), 3);
+    for idx in 0..pieces {
+        let mut material = asteroid.material / pieces;
+        if idx == 0 {
+            material = material + asteroid.material % pieces;
+        }
+        let angle = asteroid.rng.f32(std::f32::consts::TAU);
+        commands
+            .spawn_bundle(SpriteSheetBundle {
+                sprite: sprite.clone(),
+                texture_atlas: atlas.clone(),
+                transform: Transform::from_translation(position),
+                ..Default::default()
+            })
+            .insert(Fragment {
+                material,
+                timeout: Timer::from_seconds(6.0 + asteroid.rng.f32(2.), false),
+                direction: Vec3::new(angle.sin(), angle.cos(), 0.0),
+            })
+            .insert(CleanupEvent);
+    }
 }
 
 ////////////////////////
@@ -286,202 +661,359 @@ pub(crate) fn event_mode_dialog(
 
 pub(crate) fn lasers_movement(
     time: Res<Time>,
-    mut commands: Commands,
-    mut query: Query<(Entity, &mut Laser, &mut Transform), With<CleanupEvent>>,
+    mut query: Query<(&mut Laser, &mut Visibility, &mut Transform)>,
 ) {
-    for (entity, mut laser, mut transform) in query.iter_mut() {
+    for (mut laser, mut visibility, mut transform) in query.iter_mut() {
+        if !visibility.is_visible {
+            continue;
+        }
         laser.timeout.tick(time.delta());
         if laser.timeout.finished() {
-            commands.entity(entity).despawn_recursive();
+            visibility.is_visible = false;
         } else {
-            let move_speed = 12. * time.delta_seconds() * TILE_SIZE * 0.4;
+            let move_speed = laser.speed * time.delta_seconds() * TILE_SIZE;
             transform.translation = transform.translation + (laser.direction * move_speed);
         }
     }
 }
 
+// Note: Laser hits are found once, the hit systems only read the stream.
+// Enemies and asteroids are hit by the player, the player by everyone else
+pub(crate) fn detect_laser_hits(
+    mut hash: Local<SpatialHash<(Entity, bool)>>,
+    mut laser_hits: EventWriter<LaserHit>,
+    player_query: Query<&Player>,
+    collider_query: Query<(Entity, &Collider, &Transform, Option<&Player>)>,
+    laser_query: Query<(Entity, &Laser, &Visibility, &Transform)>,
+) {
+    let player = player_query.single();
+    if !player.active {
+        return;
+    }
+
+    hash.clear();
+    for (entity, collider, transform, is_player) in collider_query.iter() {
+        let position = transform.translation.truncate();
+        hash.insert((entity, is_player.is_none()), position, collider.radius);
+    }
+
+    for (entity, laser, visibility, transform) in laser_query.iter() {
+        if !visibility.is_visible {
+            continue;
+        }
+        let position = transform.translation.truncate();
+        let hit = hash.closest(position, 0., |(_, hostile)| *hostile == laser.from_player);
+        if let Some((target, _)) = hit {
+            laser_hits.send(LaserHit {
+                laser: entity,
+                target,
+                from_player: laser.from_player,
+            });
+        }
+    }
+}
+
 pub(crate) fn lasers_enemy_hits(
-    time: Res<Time>,
-    mut commands: Commands,
+    mut laser_hits: EventReader<LaserHit>,
     mut game_over: EventWriter<GameOverEvent>,
-    mut laser_query: Query<
-        (Entity, &mut Laser, &mut Transform),
-        (With<EnemyLaser>, Without<Player>),
-    >,
-    mut player_query: Query<
-        (&mut Player, &mut HealthRecource, &mut Transform),
-        Without<EnemyLaser>,
-    >,
+    mut laser_query: Query<(&Laser, &mut Visibility)>,
+    mut player_query: Query<(&mut Player, &mut HealthRecource)>,
+) {
+    for hit in laser_hits.iter().filter(|hit| !hit.from_player) {
+        let (mut player, mut player_health) = match player_query.get_mut(hit.target) {
+            Ok(player) => player,
+            Err(_) => continue,
+        };
+        if !player.active {
+            break;
+        }
+        let (laser, mut visibility) = match laser_query.get_mut(hit.laser) {
+            Ok(laser) => laser,
+            Err(_) => continue,
+        };
+        visibility.is_visible = false;
+
+        let damage = laser.damage.max(0) as u16;
+        player_health.value = player_health.value.saturating_sub(damage);
+        log::info!("health: {}", player_health.value);
+        if player_health.value < 1 {
+            player.active = false;
+            game_over.send(GameOverEvent {
+                message: "Your ship was destroyed.".to_string(),
+            })
+        }
+    }
+}
+
+pub(crate) fn lasers_player_hits(
+    mut commands: Commands,
+    mut laser_hits: EventReader<LaserHit>,
+    mut event_done: EventWriter<EventDone>,
+    mut player_query: Query<&mut Player>,
+    mut laser_query: Query<(&Laser, &mut Visibility)>,
+    mut enemy_query: Query<&mut Enemy>,
 ) {
-    let (mut player, mut player_health, player_transform) = player_query.single_mut();
+    let mut player = player_query.single_mut();
     if !player.active {
         return;
     }
-    let max_dist = TILE_SIZE * 0.25;
-    for (entity, mut _laser, transform) in laser_query.iter_mut() {
-        let pos = Vec2 {
-            x: transform.translation.x,
-            y: transform.translation.y,
+
+    for hit in laser_hits.iter().filter(|hit| hit.from_player) {
+        let mut enemy = match enemy_query.get_mut(hit.target) {
+            Ok(enemy) => enemy,
+            Err(_) => continue,
         };
-        let dist = pos.distance(Vec2 {
-            x: player_transform.translation.x,
-            y: player_transform.translation.y,
-        });
+        let (laser, mut visibility) = match laser_query.get_mut(hit.laser) {
+            Ok(laser) => laser,
+            Err(_) => continue,
+        };
+        visibility.is_visible = false;
 
-        if max_dist > dist {
-            commands.entity(entity).despawn_recursive();
+        // Note: Lasers landing on a wreck in the same frame count for nothing
+        if enemy.hp < 1 {
+            continue;
+        }
+        enemy.hp = enemy.hp - laser.damage;
+        if enemy.hp < 1 {
+            commands.entity(hit.target).despawn_recursive();
+            clear_target(&mut player, &mut event_done);
+        }
+    }
+}
 
-            if (player_health.value > 0) {
-                player_health.value = player_health.value - 1;
+// Note: The last ship gone ends the fight, shot down, rammed or escaped
+fn clear_target(player: &mut Player, event_done: &mut EventWriter<EventDone>) {
+    if player.targets > 0 {
+        player.targets = player.targets - 1;
+        if player.targets == 0 {
+            event_done.send(EventDone { count: 1 })
+        }
+    }
+}
+
+pub(crate) fn lasers_asteroid_hits(
+    mut commands: Commands,
+    world_assets: Res<WorldAssets>,
+    mut laser_hits: EventReader<LaserHit>,
+    mut player_query: Query<&mut Player>,
+    mut laser_query: Query<(&Laser, &mut Visibility)>,
+    mut asteroid_query: Query<(&mut Asteroid, &Transform)>,
+) {
+    let mut player = player_query.single_mut();
+    if !player.active {
+        return;
+    }
+
+    for hit in laser_hits.iter().filter(|hit| hit.from_player) {
+        let (mut asteroid, transform) = match asteroid_query.get_mut(hit.target) {
+            Ok(asteroid) => asteroid,
+            Err(_) => continue,
+        };
+        let (laser, mut visibility) = match laser_query.get_mut(hit.laser) {
+            Ok(laser) => laser,
+            Err(_) => continue,
+        };
+        visibility.is_visible = false;
+
+        if asteroid.hp < 1 {
+            continue;
+        }
+        asteroid.hp = asteroid.hp - laser.damage;
+        if asteroid.hp < 1 {
+            commands.entity(hit.target).despawn_recursive();
+            spawn_fragments(
+                &mut commands,
+                &mut asteroid,
+                transform.translation,
+                world_assets.base_space_sheet.clone(),
+            );
+            if player.targets > 0 {
+                player.targets = player.targets - 1;
             }
-            log::info!("health: {}", player_health.value);
-            if player_health.value < 1 {
-                player.active = false;
-                game_over.send(GameOverEvent {
-                    message: "Your ship was destroyed.".to_string(),
-                })
+        }
+    }
+}
+
+pub(crate) fn asteroids_spin(time: Res<Time>, mut query: Query<(&Asteroid, &mut Transform)>) {
+    for (asteroid, mut transform) in query.iter_mut() {
+        transform.rotate_z(asteroid.spin * time.delta_seconds());
+    }
+}
+
+pub(crate) fn fragments_movement(
+    time: Res<Time>,
+    mut commands: Commands,
+    mut player_query: Query<(&Transform, &mut CargoRecource), (With<Player>, Without<Fragment>)>,
+    mut fragment_query: Query<(Entity, &mut Fragment, &mut Transform)>,
+) {
+    let (player_transform, mut cargo) = player_query.single_mut();
+    for (entity, mut fragment, mut transform) in fragment_query.iter_mut() {
+        let delta = player_transform.translation - transform.translation;
+        let dist = delta.truncate().length();
+        if dist < FRAGMENT_PICKUP {
+            commands.entity(entity).despawn_recursive();
+            let room = cargo.max - cargo.value;
+            if fragment.material > room {
+                log::info!("cargo full, lost {} material", fragment.material - room);
             }
+            cargo.value = cargo.value + u16::min(fragment.material, room);
+            continue;
+        }
+
+        // Note: Fragments left alone drift off and are lost
+        fragment.timeout.tick(time.delta());
+        if fragment.timeout.finished() {
+            commands.entity(entity).despawn_recursive();
+            continue;
+        }
+
+        let speed = time.delta_seconds() * TILE_SIZE;
+        if dist < FRAGMENT_RANGE {
+            let pull = Vec3::new(delta.x, delta.y, 0.0).normalize();
+            transform.translation = transform.translation + pull * speed * 4.;
+        } else {
+            transform.translation = transform.translation + fragment.direction * speed * 0.3;
         }
     }
 }
 
-pub(crate) fn lasers_player_hits(
+pub(crate) fn harvest_star(
+    time: Res<Time>,
     mut commands: Commands,
     mut event_done: EventWriter<EventDone>,
-    mut player_query: Query<&mut Player, (Without<EnemyLaser>, Without<PlayerLaser>)>,
-    mut laser_query: Query<(Entity, &mut Laser, &mut Transform), With<PlayerLaser>>,
-    mut enemy_query: Query<
-        (Entity, &mut Enemy, &mut Transform),
-        (With<CleanupEvent>, Without<PlayerLaser>),
+    mut game_over: EventWriter<GameOverEvent>,
+    mut star_query: Query<(Entity, &mut Star, &Transform), Without<Player>>,
+    mut player_query: Query<
+        (
+            &mut Player,
+            &Transform,
+            &mut HealthRecource,
+            &mut EnergyRecource,
+        ),
+        Without<Star>,
     >,
 ) {
-    let mut player = player_query.single_mut();
+    let (mut player, transform, mut health, mut energy) = player_query.single_mut();
     if !player.active {
         return;
     }
 
-    let max_dist = TILE_SIZE * 0.25;
-    for (l_entity, mut laser, mut l_transform) in laser_query.iter_mut() {
-        let pos = Vec2 {
-            x: l_transform.translation.x,
-            y: l_transform.translation.y,
-        };
-        for (e_entity, mut enemy, mut e_transform) in enemy_query.iter_mut() {
-            let dist = pos.distance(Vec2 {
-                x: e_transform.translation.x,
-                y: e_transform.translation.y,
-            });
+    for (entity, mut star, star_transform) in star_query.iter_mut() {
+        let dist = transform
+            .translation
+            .truncate()
+            .distance(star_transform.translation.truncate());
 
-            if max_dist > dist {
-                commands.entity(l_entity).despawn_recursive();
-
-                enemy.hp = enemy.hp - 1;
-                if enemy.hp < 1 {
-                    commands.entity(e_entity).despawn_recursive();
-                    if player.targets > 0 {
-                        player.targets = player.targets - 1;
-                        if player.targets == 0 {
-                            event_done.send(EventDone { count: 1 })
-                        }
-                    }
-                }
+        if dist < star.inner {
+            // Note: Heat damage ticks while inside, the timer keeps running outside
+            if star.heat.tick(time.delta()).just_finished() && health.value > 0 {
+                health.value = health.value - 1;
+                log::info!("health: {}", health.value);
             }
+            if health.value < 1 {
+                player.active = false;
+                game_over.send(GameOverEvent {
+                    message: "Your ship burned up in the star.".to_string(),
+                });
+            }
+            continue;
+        }
+        star.heat.tick(time.delta());
+        if dist > star.outer {
+            continue;
+        }
+
+        let amount = f32::min(star.rate * time.delta_seconds(), star.energy);
+        star.energy = star.energy - amount;
+        star.pending = star.pending + amount;
+        if star.pending >= 1. {
+            let whole = star.pending.floor();
+            star.pending = star.pending - whole;
+            energy.value = u16::min(energy.value + whole as u16, energy.max);
+        }
+
+        if star.energy <= 0. {
+            commands.entity(entity).despawn_recursive();
+            event_done.send(EventDone { count: 1 });
         }
     }
 }
 
+fn on_mining_done(
+    mut commands: Commands,
+    mut event_done: EventWriter<EventDone>,
+    field_query: Query<Entity, With<MiningField>>,
+    asteroid_query: Query<(), With<Asteroid>>,
+    fragment_query: Query<(), With<Fragment>>,
+) {
+    if !asteroid_query.is_empty() || !fragment_query.is_empty() {
+        return;
+    }
+    for entity in field_query.iter() {
+        commands.entity(entity).despawn_recursive();
+        event_done.send(EventDone { count: 1 });
+    }
+}
+
 pub(crate) fn enemy_fire_system(
     time: Res<Time>,
-    mut commands: Commands,
-    world_assets: Res<WorldAssets>,
-    mut enemy_query: Query<(&mut Enemy, &mut Transform), Without<Player>>,
-    mut player_query: Query<(&mut Player, &mut Transform), Without<Enemy>>,
+    mut fire_events: EventWriter<FireEvent>,
+    mut enemy_query: Query<(&mut Enemy, &EnemyAi, &Weapon, &Transform), Without<Player>>,
+    player_query: Query<(&Player, &Transform), Without<Enemy>>,
 ) {
-    let mut rng = Shift64::new(rand::random());
-    let (player, mut player_transform) = player_query.single_mut();
+    let (player, player_transform) = player_query.single();
     if !player.active {
         return;
     }
-    let mut laser = TextureAtlasSprite::new(46);
-    laser.custom_size = Some(Vec2 {
-        x: TILE_SIZE * 0.05,
-        y: TILE_SIZE * 0.5,
-    });
-    for (mut enemy, mut enemy_transform) in enemy_query.iter_mut() {
+    for (mut enemy, ai, weapon, enemy_transform) in enemy_query.iter_mut() {
         // Update timer
         enemy.timeout.tick(time.delta());
-        if enemy.timeout.finished() {
-            enemy.timeout = Timer::from_seconds(0.6 + (0.01 * rng.f32(160.)), false);
-            let from = Vec2 {
-                x: enemy_transform.translation.x,
-                y: enemy_transform.translation.y,
-            };
-            spawn_enemy_laser(
-                &mut commands,
+        if enemy.timeout.finished() && ai.behaviour.fires() {
+            // Note: Each enemy owns its generator, kill order does not shift the others
+            let tuning = ai.archetype.tuning();
+            let delay = tuning.fire_delay + (0.01 * enemy.rng.f32(tuning.fire_spread * 100.));
+            enemy.timeout = Timer::from_seconds(delay, false);
+            let from = enemy_transform.translation.truncate();
+            fire_events.send(FireEvent {
                 from,
-                Vec2 {
-                    x: player_transform.translation.x - from.x,
-                    y: player_transform.translation.y - from.y,
-                },
-                world_assets.base_space_sheet.clone(),
-                laser.clone(),
-            )
+                aim: player_transform.translation.truncate() - from,
+                error: weapon.aim_error(&mut enemy.rng),
+                from_player: false,
+                weapon: weapon.clone(),
+            });
         }
     }
 }
 
-fn spawn_enemy_laser(
-    commands: &mut Commands,
-    from: Vec2,
-    to: Vec2,
-    atlas: Handle<TextureAtlas>,
-    sprite: TextureAtlasSprite,
-) {
-    let delta = to.x.atan2(to.y);
-
-    commands
-        .spawn_bundle(SpriteSheetBundle {
-            sprite: sprite,
-            texture_atlas: atlas,
-            transform: Transform {
-                rotation: Quat::from_axis_angle(-Vec3::Z, delta),
-                translation: Vec3 {
-                    x: from.x,
-                    y: from.y,
-                    z: 5.0,
-                },
-                ..Default::default()
-            },
-            ..Default::default()
-        })
-        .insert(CleanupEvent)
-        .insert(EnemyLaser)
-        .insert(Laser {
-            direction: Vec3::new(to.x, to.y, 0.0).normalize(),
-            timeout: Timer::from_seconds(1.6, false),
-        });
-}
-
 pub(crate) fn player_fire_system(
-    mut commands: Commands,
+    time: Res<Time>,
     windows: Res<Windows>,
-    world_assets: Res<WorldAssets>,
     mut buttons: ResMut<Input<MouseButton>>,
+    mut encounter: ResMut<EncounterRng>,
+    mut fire_events: EventWriter<FireEvent>,
     mut game_over: EventWriter<GameOverEvent>,
-    mut player_query: Query<(&mut Player, &mut Transform, &mut EnergyRecource)>,
+    mut player_query: Query<(&mut Player, &mut Loadout, &Transform, &mut EnergyRecource)>,
     camera_query: Query<(&Camera, &GlobalTransform), (With<PlayerCamera>, Without<Player>)>,
 ) {
-    let (mut player, mut player_transform, mut energy) = player_query.single_mut();
+    let (mut player, mut loadout, player_transform, mut energy) = player_query.single_mut();
     if !player.active {
         return;
     }
 
-    if buttons.just_pressed(MouseButton::Left) {
+    // Note: Every weapon cools down, switching does not skip the wait
+    for weapon in loadout.weapons.iter_mut() {
+        weapon.cooldown.tick(time.delta());
+    }
+    let weapon = loadout.current_mut();
+    let trigger = match weapon.hold {
+        true => buttons.pressed(MouseButton::Left),
+        false => buttons.just_pressed(MouseButton::Left),
+    };
+    if trigger && weapon.ready() {
         buttons.clear();
+        weapon.reload();
 
-        if (energy.value > 0) {
-            energy.value = energy.value - 1;
-        }
+        energy.value = energy.value.saturating_sub(weapon.energy);
         if energy.value < 1 {
             player.active = false;
             game_over.send(GameOverEvent {
@@ -498,12 +1030,6 @@ pub(crate) fn player_fire_system(
             windows.get_primary().unwrap()
         };
         if let Some(screen_pos) = wnd.cursor_position() {
-            let mut laser = TextureAtlasSprite::new(47);
-            laser.custom_size = Some(Vec2 {
-                x: TILE_SIZE * 0.05,
-                y: TILE_SIZE * 0.5,
-            });
-
             // Convert window position to gpu coordinates
             let window_size = Vec2::new(wnd.width() as f32, wnd.height() as f32);
             let ndc = (screen_pos / window_size) * 2.0 - Vec2::ONE;
@@ -512,31 +1038,14 @@ pub(crate) fn player_fire_system(
 
             // use it to convert ndc to world-space coordinates
             let world_pos = ndc_to_world.project_point3(ndc.extend(-1.0));
-            let delta_x = world_pos.x - player_transform.translation.x;
-            let delta_y = world_pos.y - player_transform.translation.y;
-            let delta = delta_x.atan2(delta_y);
-
-            commands
-                .spawn_bundle(SpriteSheetBundle {
-                    sprite: laser,
-                    texture_atlas: world_assets.base_space_sheet.clone(),
-                    transform: Transform {
-                        rotation: Quat::from_axis_angle(-Vec3::Z, delta),
-                        translation: Vec3 {
-                            x: player_transform.translation.x,
-                            y: player_transform.translation.y,
-                            z: 4.0,
-                        },
-                        ..Default::default()
-                    },
-                    ..Default::default()
-                })
-                .insert(CleanupEvent)
-                .insert(PlayerLaser)
-                .insert(Laser {
-                    direction: Vec3::new(delta_x, delta_y, 0.0).normalize(),
-                    timeout: Timer::from_seconds(1.6, false),
-                });
+            let from = player_transform.translation.truncate();
+            fire_events.send(FireEvent {
+                from,
+                aim: world_pos.truncate() - from,
+                error: weapon.aim_error(&mut encounter.rng),
+                from_player: true,
+                weapon: weapon.clone(),
+            });
         }
     }
 }
@@ -545,7 +1054,7 @@ pub(crate) fn event_mode_stats(commands: &mut Commands, font: &Handle<Font>) ->
     let root = commands
         .spawn_bundle(NodeBundle {
             style: Style {
-                size: Size::new(Val::Px(TILE_SIZE * 8.), Val::Percent(100.)),
+                size: Size::new(Val::Px(TILE_SIZE * 11.), Val::Percent(100.)),
                 padding: UiRect::new(Val::Px(0.), Val::Px(12.), Val::Px(0.), Val::Px(0.)),
                 flex_direction: FlexDirection::Row,
                 justify_content: JustifyContent::SpaceBetween,
@@ -623,6 +1132,70 @@ pub(crate) fn event_mode_stats(commands: &mut Commands, font: &Handle<Font>) ->
                         ))
                         .insert(EnergyText);
                 });
+            parent
+                .spawn_bundle(NodeBundle {
+                    style: Style {
+                        flex_direction: FlexDirection::Row,
+                        size: Size::new(Val::Px(TILE_SIZE * 2.2), Val::Px(65.0)),
+                        justify_content: JustifyContent::SpaceBetween,
+                        align_items: AlignItems::Center,
+                        ..Default::default()
+                    },
+                    color: Color::rgba(0., 0., 0., 0.0).into(),
+                    ..Default::default()
+                })
+                .with_children(|parent| {
+                    parent.spawn_bundle(TextBundle::from_section(
+                        "cargo:",
+                        TextStyle {
+                            font: font.clone(),
+                            font_size: 24.0,
+                            color: gui::TEXT_BUTTON,
+                        },
+                    ));
+                    parent
+                        .spawn_bundle(TextBundle::from_section(
+                            format!("0/{}", CARGO_CAPACITY),
+                            TextStyle {
+                                font: font.clone(),
+                                font_size: 24.0,
+                                color: gui::TEXT_BUTTON,
+                            },
+                        ))
+                        .insert(CargoText);
+                });
+            parent
+                .spawn_bundle(NodeBundle {
+                    style: Style {
+                        flex_direction: FlexDirection::Row,
+                        size: Size::new(Val::Px(TILE_SIZE * 2.8), Val::Px(65.0)),
+                        justify_content: JustifyContent::SpaceBetween,
+                        align_items: AlignItems::Center,
+                        ..Default::default()
+                    },
+                    color: Color::rgba(0., 0., 0., 0.0).into(),
+                    ..Default::default()
+                })
+                .with_children(|parent| {
+                    parent.spawn_bundle(TextBundle::from_section(
+                        "weapon:",
+                        TextStyle {
+                            font: font.clone(),
+                            font_size: 24.0,
+                            color: gui::TEXT_BUTTON,
+                        },
+                    ));
+                    parent
+                        .spawn_bundle(TextBundle::from_section(
+                            format!("1 laser"),
+                            TextStyle {
+                                font: font.clone(),
+                                font_size: 24.0,
+                                color: gui::TEXT_BUTTON,
+                            },
+                        ))
+                        .insert(WeaponText);
+                });
         })
         .id();
 
@@ -0,0 +1,326 @@
+use super::*;
+
+// Distances are in tiles, speeds in tiles per second
+const SEPARATION: f32 = 1.2;
+const SEPARATION_FORCE: f32 = 2.0;
+const ESCAPE_RANGE: f32 = 16.;
+const KAMIKAZE_HIT: f32 = 0.4;
+const KAMIKAZE_DAMAGE: u16 = 3;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Behaviour {
+    #[default]
+    Approach,
+    Orbit,
+    Strafe,
+    Flee,
+    Kamikaze,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Archetype {
+    Small,
+    Large,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Tuning {
+    pub speed: f32,
+    pub accel: f32,   // Share of the steering applied per second
+    pub range: f32,   // Preferred distance to the player
+    pub flee_hp: i32, // At or below this hull the ship runs
+    pub fire_delay: f32,
+    pub fire_spread: f32,
+}
+
+#[derive(Component)]
+pub struct EnemyAi {
+    pub behaviour: Behaviour,
+    pub preferred: Behaviour, // Taken up once in range
+    pub archetype: Archetype,
+    pub velocity: Vec2,
+    pub side: f32, // Circling direction, flips while strafing
+    pub switch: Timer,
+}
+
+impl Archetype {
+    pub fn tuning(&self) -> Tuning {
+        match self {
+            Archetype::Small => Tuning {
+                speed: 3.0,
+                accel: 3.0,
+                range: 3.5,
+                flee_hp: 1,
+                fire_delay: 0.6,
+                fire_spread: 1.6,
+            },
+            Archetype::Large => Tuning {
+                speed: 1.4,
+                accel: 1.2,
+                range: 5.0,
+                flee_hp: 0,
+                fire_delay: 0.9,
+                fire_spread: 0.8,
+            },
+        }
+    }
+}
+
+impl CombatMix {
+    // Weights for orbit, strafe and kamikaze
+    fn weights(&self) -> [u32; 3] {
+        match self {
+            CombatMix::Balanced => [2, 2, 1],
+            CombatMix::Hunters => [1, 3, 0],
+            CombatMix::Swarm => [1, 1, 3],
+            CombatMix::Skirmish => [3, 1, 0],
+        }
+    }
+
+    // Note: Large ships are too slow to ram, they only orbit or strafe
+    pub fn roll(&self, rng: &mut Shift64, archetype: Archetype) -> Behaviour {
+        let mut weights = self.weights();
+        if archetype == Archetype::Large {
+            weights[2] = 0;
+        }
+        match rng.weighted(&weights) {
+            Some(0) => Behaviour::Orbit,
+            Some(1) => Behaviour::Strafe,
+            Some(_) => Behaviour::Kamikaze,
+            None => Behaviour::Orbit,
+        }
+    }
+}
+
+impl Behaviour {
+    pub fn fires(&self) -> bool {
+        matches!(
+            self,
+            Behaviour::Approach | Behaviour::Orbit | Behaviour::Strafe
+        )
+    }
+
+    // Next state from the hull left and the distance to the player
+    pub fn next(self, preferred: Behaviour, hp: i32, dist: f32, tuning: &Tuning) -> Behaviour {
+        match self {
+            Behaviour::Flee | Behaviour::Kamikaze => self,
+            _ if hp <= tuning.flee_hp => Behaviour::Flee,
+            Behaviour::Approach if preferred == Behaviour::Kamikaze => Behaviour::Kamikaze,
+            Behaviour::Approach if dist <= tuning.range => preferred,
+            Behaviour::Orbit | Behaviour::Strafe if dist > tuning.range * 1.6 => {
+                Behaviour::Approach
+            }
+            _ => self,
+        }
+    }
+
+    // Velocity the ship wants, offset points from the ship to the player
+    pub fn desired(&self, offset: Vec2, side: f32, tuning: &Tuning) -> Vec2 {
+        let dist = offset.length();
+        if dist <= f32::EPSILON {
+            return Vec2::ZERO;
+        }
+        let toward = offset / dist;
+        let around = Vec2::new(-toward.y, toward.x) * side;
+
+        // Note: Circling ships drift back on to their preferred range
+        let keep = toward * ((dist - tuning.range) / tuning.range).clamp(-1., 1.);
+        match self {
+            Behaviour::Approach => toward * tuning.speed,
+            Behaviour::Orbit => (around + keep).normalize_or_zero() * tuning.speed,
+            Behaviour::Strafe => (around + keep * 0.5) * tuning.speed * 0.8,
+            Behaviour::Flee => -toward * tuning.speed * 1.2,
+            Behaviour::Kamikaze => toward * tuning.speed * 1.6,
+        }
+    }
+}
+
+// Push away from every ship inside the separation range
+pub fn separation(position: Vec2, others: &[Vec2]) -> Vec2 {
+    let mut push = Vec2::ZERO;
+    for other in others {
+        let away = position - *other;
+        let dist = away.length();
+        if dist > f32::EPSILON && dist < SEPARATION {
+            push = push + away / dist * (SEPARATION - dist) / SEPARATION;
+        }
+    }
+    push * SEPARATION_FORCE
+}
+
+pub fn steer(velocity: Vec2, desired: Vec2, accel: f32, delta: f32) -> Vec2 {
+    velocity + (desired - velocity) * f32::min(accel * delta, 1.)
+}
+
+impl EnemyAi {
+    pub fn new(rng: &mut Shift64, archetype: Archetype, mix: CombatMix) -> Self {
+        Self {
+            behaviour: Behaviour::Approach,
+            preferred: mix.roll(rng, archetype),
+            archetype,
+            velocity: Vec2::ZERO,
+            side: match rng.bool(0.5) {
+                true => 1.,
+                false => -1.,
+            },
+            switch: Timer::from_seconds(1.0 + rng.f32(1.0), true),
+        }
+    }
+}
+
+pub(crate) fn enemy_ai_system(
+    time: Res<Time>,
+    mut commands: Commands,
+    mut event_done: EventWriter<EventDone>,
+    mut game_over: EventWriter<GameOverEvent>,
+    mut player_query: Query<(&mut Player, &Transform, &mut HealthRecource), Without<Enemy>>,
+    mut enemy_query: Query<(Entity, &mut Enemy, &mut EnemyAi, &mut Transform), Without<Player>>,
+) {
+    let (mut player, player_transform, mut health) = player_query.single_mut();
+    if !player.active {
+        return;
+    }
+
+    let target = player_transform.translation.truncate() / TILE_SIZE;
+    let others: Vec<Vec2> = enemy_query
+        .iter()
+        .map(|(_, _, _, transform)| transform.translation.truncate() / TILE_SIZE)
+        .collect();
+
+    let delta = time.delta_seconds();
+    for (entity, mut enemy, mut ai, mut transform) in enemy_query.iter_mut() {
+        // Note: Wrecks wait for their despawn, they were already counted
+        if enemy.hp < 1 {
+            continue;
+        }
+        let tuning = ai.archetype.tuning();
+        let position = transform.translation.truncate() / TILE_SIZE;
+        let offset = target - position;
+        let dist = offset.length();
+
+        ai.behaviour = ai.behaviour.next(ai.preferred, enemy.hp, dist, &tuning);
+        if ai.behaviour == Behaviour::Strafe && ai.switch.tick(time.delta()).just_finished() {
+            ai.side = -ai.side;
+        }
+
+        // Note: Rams and escapes take the ship out of the fight
+        if ai.behaviour == Behaviour::Kamikaze && dist < KAMIKAZE_HIT {
+            enemy.hp = 0;
+            commands.entity(entity).despawn_recursive();
+            health.value = health.value.saturating_sub(KAMIKAZE_DAMAGE);
+            log::info!("health: {}", health.value);
+            if health.value < 1 {
+                player.active = false;
+                game_over.send(GameOverEvent {
+                    message: "Your ship was destroyed.".to_string(),
+                });
+                return;
+            }
+            clear_target(&mut player, &mut event_done);
+            continue;
+        }
+        if ai.behaviour == Behaviour::Flee && dist > ESCAPE_RANGE {
+            enemy.hp = 0;
+            commands.entity(entity).despawn_recursive();
+            clear_target(&mut player, &mut event_done);
+            continue;
+        }
+
+        let desired = ai.behaviour.desired(offset, ai.side, &tuning);
+        let desired = desired + separation(position, &others);
+        ai.velocity = steer(ai.velocity, desired, tuning.accel, delta);
+
+        let moved = ai.velocity * TILE_SIZE * delta;
+        transform.translation.x = transform.translation.x + moved.x;
+        transform.translation.y = transform.translation.y + moved.y;
+
+        // Fleeing ships face where they go, everyone else faces the player
+        let facing = match ai.behaviour {
+            Behaviour::Flee => ai.velocity,
+            _ => offset,
+        };
+        if facing.length_squared() > f32::EPSILON {
+            transform.rotation = Quat::from_axis_angle(-Vec3::Z, facing.x.atan2(facing.y));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{separation, Archetype, Behaviour, CombatMix, Shift64, Vec2};
+
+    #[test]
+    fn behaviour_states() {
+        let tuning = Archetype::Small.tuning();
+        let next = |b: Behaviour, p: Behaviour, hp: i32, d: f32| b.next(p, hp, d, &tuning);
+
+        assert_eq!(
+            next(Behaviour::Approach, Behaviour::Orbit, 4, 8.),
+            Behaviour::Approach
+        );
+        assert_eq!(
+            next(Behaviour::Approach, Behaviour::Orbit, 4, 3.),
+            Behaviour::Orbit
+        );
+        assert_eq!(
+            next(Behaviour::Approach, Behaviour::Kamikaze, 4, 8.),
+            Behaviour::Kamikaze
+        );
+        assert_eq!(
+            next(Behaviour::Strafe, Behaviour::Strafe, 4, 7.),
+            Behaviour::Approach
+        );
+        assert_eq!(
+            next(Behaviour::Orbit, Behaviour::Orbit, 1, 3.),
+            Behaviour::Flee
+        );
+        assert_eq!(
+            next(Behaviour::Kamikaze, Behaviour::Kamikaze, 1, 3.),
+            Behaviour::Kamikaze
+        );
+
+        let large = Archetype::Large.tuning();
+        assert_eq!(
+            Behaviour::Orbit.next(Behaviour::Orbit, 1, 4., &large),
+            Behaviour::Orbit
+        );
+    }
+
+    #[test]
+    fn behaviour_steering() {
+        let tuning = Archetype::Small.tuning();
+        let offset = Vec2::new(0., 6.);
+
+        let approach = Behaviour::Approach.desired(offset, 1., &tuning);
+        assert!((approach - Vec2::new(0., tuning.speed)).length() < 0.001);
+        assert!(Behaviour::Flee.desired(offset, 1., &tuning).y < 0.);
+
+        // At the preferred range orbiting is a pure side step
+        let orbit = Behaviour::Orbit.desired(Vec2::new(0., tuning.range), 1., &tuning);
+        assert!(orbit.y.abs() < 0.001 && orbit.x < 0.);
+
+        let push = separation(Vec2::ZERO, &[Vec2::ZERO, Vec2::new(0.5, 0.)]);
+        assert!(push.x < 0. && push.y == 0.);
+        assert_eq!(separation(Vec2::ZERO, &[Vec2::new(4., 0.)]), Vec2::ZERO);
+    }
+
+    #[test]
+    fn combat_mix_roll() {
+        let mut rng = Shift64::new(7);
+        for mix in [
+            CombatMix::Balanced,
+            CombatMix::Hunters,
+            CombatMix::Swarm,
+            CombatMix::Skirmish,
+        ] {
+            for _ in 0..200 {
+                assert_ne!(mix.roll(&mut rng, Archetype::Large), Behaviour::Kamikaze);
+            }
+        }
+        let rams = (0..400)
+            .filter(|_| CombatMix::Swarm.roll(&mut rng, Archetype::Small) == Behaviour::Kamikaze)
+            .count();
+        assert!(rams > 200 && rams < 400, "{}", rams);
+        assert!((0..200).all(|_| CombatMix::Hunters.roll(&mut rng, Archetype::Small).fires()));
+    }
+}
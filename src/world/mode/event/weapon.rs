@@ -0,0 +1,419 @@
+use std::f32::consts::PI;
+
+use super::*;
+
+// Hidden lasers spawned with the event, shots reuse them instead of spawning
+const POOL_SIZE: usize = 128;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum WeaponKind {
+    Laser,
+    Spread,
+    Beam,
+    Missile,
+}
+
+// How a ship shoots, speeds are in tiles per second
+#[derive(Component, Clone, Debug)]
+pub struct Weapon {
+    pub kind: WeaponKind,
+    pub fire_rate: f32, // Shots per second
+    pub energy: u16,    // Energy used per shot
+    pub speed: f32,
+    pub spread: f32, // Largest aim error either side in radians
+    pub shots: u16,  // Lasers per shot, fanned out evenly
+    pub fan: f32,    // Angle between the outer lasers of a shot
+    pub homing: f32, // Turn rate in radians per second towards the closest target
+    pub damage: i32,
+    pub lifetime: f32,
+    pub sprite: usize,
+    pub tint: Color,
+    pub hold: bool, // Keeps firing while the button is held
+    pub ammo: Option<u16>,
+    pub cooldown: Timer,
+}
+
+// Weapons the player carries, the number keys pick the active one
+#[derive(Component, Clone, Debug)]
+pub struct Loadout {
+    pub weapons: Vec<Weapon>,
+    pub active: usize,
+}
+
+pub struct FireEvent {
+    pub from: Vec2,
+    pub aim: Vec2,
+    pub error: f32, // Aim error in radians, drawn by the shooter
+    pub from_player: bool,
+    pub weapon: Weapon,
+}
+
+// Generator for the player's aim error, forked from the encounter layout
+pub struct EncounterRng {
+    pub rng: Shift64,
+}
+
+#[derive(Component)]
+pub struct ProjectilePool;
+
+#[derive(Component)]
+pub struct WeaponText;
+
+impl Default for Weapon {
+    fn default() -> Self {
+        Self {
+            kind: WeaponKind::Laser,
+            fire_rate: 4.0,
+            energy: 1,
+            speed: 4.8,
+            spread: 0.0,
+            shots: 1,
+            fan: 0.0,
+            homing: 0.0,
+            damage: 1,
+            lifetime: 1.6,
+            sprite: 47,
+            tint: Color::WHITE,
+            hold: false,
+            ammo: None,
+            cooldown: Timer::from_seconds(0., false),
+        }
+    }
+}
+
+impl Weapon {
+    pub fn player_laser() -> Self {
+        Weapon::default()
+    }
+
+    pub fn spread_shot() -> Self {
+        Weapon {
+            kind: WeaponKind::Spread,
+            fire_rate: 2.0,
+            energy: 3,
+            speed: 4.4,
+            shots: 5,
+            fan: 0.6,
+            lifetime: 1.0,
+            ..Default::default()
+        }
+    }
+
+    // Note: The beam is a fast stream of short lived lasers, every one costs energy
+    pub fn beam() -> Self {
+        Weapon {
+            kind: WeaponKind::Beam,
+            fire_rate: 10.0,
+            speed: 14.0,
+            lifetime: 0.35,
+            tint: Color::rgb(0.6, 0.9, 1.0),
+            hold: true,
+            ..Default::default()
+        }
+    }
+
+    pub fn missiles() -> Self {
+        Weapon {
+            kind: WeaponKind::Missile,
+            fire_rate: 1.5,
+            energy: 0,
+            speed: 3.2,
+            homing: 4.0,
+            damage: 3,
+            lifetime: 3.0,
+            sprite: 46,
+            tint: Color::ORANGE,
+            ammo: Some(8),
+            ..Default::default()
+        }
+    }
+
+    // Note: Enemies pace their shots with the AI timer, fire_rate only holds back the player
+    pub fn enemy_laser() -> Self {
+        Weapon {
+            fire_rate: 1.0,
+            energy: 0,
+            sprite: 46,
+            ..Default::default()
+        }
+    }
+
+    pub fn heavy_laser() -> Self {
+        Weapon {
+            fire_rate: 0.5,
+            energy: 0,
+            speed: 3.6,
+            spread: 0.08,
+            damage: 2,
+            lifetime: 2.2,
+            sprite: 46,
+            ..Default::default()
+        }
+    }
+
+    pub fn ready(&self) -> bool {
+        self.cooldown.finished() && self.ammo != Some(0)
+    }
+
+    pub fn reload(&mut self) {
+        self.cooldown = Timer::from_seconds(1. / self.fire_rate, false);
+        if let Some(ammo) = self.ammo.as_mut() {
+            *ammo = ammo.saturating_sub(1);
+        }
+    }
+
+    // Note: Weapons without spread never draw from the generator
+    pub fn aim_error(&self, rng: &mut Shift64) -> f32 {
+        match self.spread > 0. {
+            true => rng.signed() * self.spread,
+            false => 0.,
+        }
+    }
+
+    // Angle of the laser at index away from the aim
+    pub fn fan_offset(&self, index: u16) -> f32 {
+        match self.shots {
+            0 | 1 => 0.,
+            shots => self.fan * (index as f32 / (shots - 1) as f32 - 0.5),
+        }
+    }
+
+    pub fn label(&self) -> String {
+        let name = match self.kind {
+            WeaponKind::Laser => "laser",
+            WeaponKind::Spread => "spread",
+            WeaponKind::Beam => "beam",
+            WeaponKind::Missile => "missile",
+        };
+        match self.ammo {
+            Some(ammo) => format!("{} {}", name, ammo),
+            None => name.to_string(),
+        }
+    }
+}
+
+impl Default for Loadout {
+    fn default() -> Self {
+        Self {
+            weapons: vec![
+                Weapon::player_laser(),
+                Weapon::spread_shot(),
+                Weapon::beam(),
+                Weapon::missiles(),
+            ],
+            active: 0,
+        }
+    }
+}
+
+impl Loadout {
+    pub fn select(&mut self, index: usize) {
+        if index < self.weapons.len() {
+            self.active = index;
+        }
+    }
+
+    pub fn current(&self) -> &Weapon {
+        &self.weapons[self.active]
+    }
+
+    pub fn current_mut(&mut self) -> &mut Weapon {
+        &mut self.weapons[self.active]
+    }
+}
+
+pub(crate) fn spawn_projectile_pool(mut commands: Commands, world_assets: Res<WorldAssets>) {
+    let mut sprite = TextureAtlasSprite::new(47);
+    sprite.custom_size = Some(Vec2 {
+        x: TILE_SIZE * 0.05,
+        y: TILE_SIZE * 0.5,
+    });
+
+    let mut lasers = Vec::new();
+    for _i in 0..POOL_SIZE {
+        let mut laser = SpriteSheetBundle {
+            sprite: sprite.clone(),
+            texture_atlas: world_assets.base_space_sheet.clone(),
+            ..Default::default()
+        };
+        laser.visibility.is_visible = false;
+        lasers.push(commands.spawn_bundle(laser).insert(Laser::default()).id());
+    }
+
+    commands
+        .spawn_bundle(TransformBundle::default())
+        .insert_bundle(VisibilityBundle::default())
+        .insert(Name::new("projectile-pool"))
+        .insert(ProjectilePool)
+        .insert(CleanupEvent)
+        .push_children(&lasers);
+}
+
+pub(crate) fn select_weapon(
+    keyboard: Res<Input<KeyCode>>,
+    mut player_query: Query<(&Player, &mut Loadout)>,
+) {
+    let (player, mut loadout) = player_query.single_mut();
+    if !player.active {
+        return;
+    }
+
+    let keys = [KeyCode::Key1, KeyCode::Key2, KeyCode::Key3, KeyCode::Key4];
+    for (index, key) in keys.iter().enumerate() {
+        if keyboard.just_pressed(*key) && loadout.active != index {
+            loadout.select(index);
+        }
+    }
+}
+
+pub(crate) fn update_weapon_text(
+    loadout_query: Query<&Loadout, With<Player>>,
+    mut text_query: Query<&mut Text, With<WeaponText>>,
+) {
+    let loadout = loadout_query.single();
+    let mut text = text_query.single_mut();
+
+    if text.sections.len() == 0 {
+        return;
+    }
+    let label = loadout.current().label();
+    text.sections[0].value = format!("{} {}", loadout.active + 1, label);
+}
+
+// Note: A hidden laser is free, shots are dropped while the pool is used up
+pub(crate) fn emit_projectiles(
+    mut fire_events: EventReader<FireEvent>,
+    pool_query: Query<&Children, With<ProjectilePool>>,
+    mut laser_query: Query<(
+        &mut Laser,
+        &mut Visibility,
+        &mut Transform,
+        &mut TextureAtlasSprite,
+    )>,
+) {
+    let children = match pool_query.get_single() {
+        Ok(children) => children,
+        Err(_) => return,
+    };
+
+    let mut free = children.iter();
+    'fire: for fire in fire_events.iter() {
+        let aim = fire.aim.normalize_or_zero();
+        if aim == Vec2::ZERO {
+            continue;
+        }
+        let weapon = &fire.weapon;
+        let aim = aim.x.atan2(aim.y) + fire.error;
+
+        for index in 0..weapon.shots {
+            let angle = aim + weapon.fan_offset(index);
+            let found = free.find(|child| match laser_query.get(**child) {
+                Ok((_, visibility, _, _)) => !visibility.is_visible,
+                Err(_) => false,
+            });
+            let (mut laser, mut visibility, mut transform, mut sprite) =
+                match found.and_then(|child| laser_query.get_mut(*child).ok()) {
+                    Some(laser) => laser,
+                    None => {
+                        log::debug!("emit_projectiles: pool is empty");
+                        break 'fire;
+                    }
+                };
+
+            *laser = Laser {
+                timeout: Timer::from_seconds(weapon.lifetime, false),
+                direction: Vec3::new(angle.sin(), angle.cos(), 0.0),
+                speed: weapon.speed,
+                damage: weapon.damage,
+                homing: weapon.homing,
+                from_player: fire.from_player,
+            };
+            transform.rotation = Quat::from_axis_angle(-Vec3::Z, angle);
+            transform.translation = Vec3 {
+                x: fire.from.x,
+                y: fire.from.y,
+                z: if fire.from_player { 4.0 } else { 5.0 },
+            };
+            sprite.index = weapon.sprite;
+            sprite.color = weapon.tint;
+            visibility.is_visible = true;
+        }
+    }
+}
+
+// Homing lasers turn towards the closest ship on the other side, never an asteroid
+pub(crate) fn lasers_homing(
+    time: Res<Time>,
+    mut laser_query: Query<(&mut Laser, &Visibility, &mut Transform)>,
+    target_query: Query<
+        (&Transform, Option<&Enemy>),
+        (With<Collider>, Without<Laser>, Without<Asteroid>),
+    >,
+) {
+    for (mut laser, visibility, mut transform) in laser_query.iter_mut() {
+        if !visibility.is_visible || laser.homing <= 0. {
+            continue;
+        }
+
+        let position = transform.translation.truncate();
+        let target = target_query
+            .iter()
+            .filter(|(_, enemy)| enemy.is_some() == laser.from_player)
+            .map(|(target, _)| target.translation.truncate() - position)
+            .min_by(|a, b| a.length_squared().total_cmp(&b.length_squared()));
+        let offset = match target {
+            Some(offset) if offset.length_squared() > f32::EPSILON => offset,
+            _ => continue,
+        };
+
+        let heading = laser.direction.x.atan2(laser.direction.y);
+        let wanted = offset.x.atan2(offset.y);
+        let mut turn = wanted - heading;
+        if turn > PI {
+            turn = turn - 2. * PI;
+        } else if turn < -PI {
+            turn = turn + 2. * PI;
+        }
+        let limit = laser.homing * time.delta_seconds();
+        let angle = heading + turn.clamp(-limit, limit);
+
+        laser.direction = Vec3::new(angle.sin(), angle.cos(), 0.0);
+        transform.rotation = Quat::from_axis_angle(-Vec3::Z, angle);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Loadout, Weapon, WeaponKind};
+
+    #[test]
+    fn weapon_fan_and_ammo() {
+        let spread = Weapon::spread_shot();
+        assert_eq!(spread.fan_offset(0), -spread.fan / 2.);
+        assert_eq!(spread.fan_offset(2), 0.);
+        assert_eq!(spread.fan_offset(spread.shots - 1), spread.fan / 2.);
+        assert_eq!(Weapon::player_laser().fan_offset(0), 0.);
+
+        let mut missiles = Weapon::missiles();
+        missiles.ammo = Some(1);
+        missiles.reload();
+        assert_eq!(missiles.ammo, Some(0));
+        assert!(!missiles.ready());
+        assert_eq!(missiles.label(), "missile 0");
+
+        let mut laser = Weapon::player_laser();
+        laser.reload();
+        assert_eq!(laser.ammo, None);
+        assert_eq!(laser.label(), "laser");
+    }
+
+    #[test]
+    fn loadout_select() {
+        let mut loadout = Loadout::default();
+        assert_eq!(loadout.current().kind, WeaponKind::Laser);
+        loadout.select(2);
+        assert_eq!(loadout.current().kind, WeaponKind::Beam);
+        loadout.select(9);
+        assert_eq!(loadout.current().kind, WeaponKind::Beam);
+    }
+}
@@ -2,12 +2,20 @@ use super::*;
 
 use crate::gui::gamehud::*;
 
+// Cleared events until the run counts as fully difficult
+const DIFFICULTY_EVENTS: f32 = 20.;
+
+const CHOICE_WIDTH: f32 = 520.;
+
 #[derive(Component)]
 pub struct HealthText;
 
 #[derive(Component)]
 pub struct EnergyText;
 
+#[derive(Component)]
+pub struct CargoText;
+
 #[derive(Clone, Copy, Default, Debug)]
 pub struct EndHexEvent {
     pub enter: bool,
@@ -18,6 +26,11 @@ pub struct StartHexEvent {
     pub seed: i64,
 }
 
+#[derive(Clone, Copy, Default, Debug)]
+pub struct ChoiceEvent {
+    pub index: usize,
+}
+
 pub struct ExploreModePlugin;
 
 impl Plugin for ExploreModePlugin {
@@ -26,12 +39,14 @@ impl Plugin for ExploreModePlugin {
 
         app.add_event::<EndHexEvent>();
         app.add_event::<StartHexEvent>();
+        app.add_event::<ChoiceEvent>();
 
         // app.add_system_set(SystemSet::on_exit(explore_grid).with_system(exit_explore_gameplay));
         // app.add_system_set(SystemSet::on_enter(explore_grid).with_system(enter_explore_gameplay));
 
         app.add_system_set(SystemSet::on_update(explore_mode).with_system(update_health_text));
         app.add_system_set(SystemSet::on_update(explore_mode).with_system(update_energy_text));
+        app.add_system_set(SystemSet::on_update(explore_mode).with_system(update_cargo_text));
 
         // Event Systems
         app.add_system_set(
@@ -42,6 +57,10 @@ impl Plugin for ExploreModePlugin {
             SystemSet::on_update(explore_mode)
                 .with_system(on_start_hex_event.after("gui-update").after("player-move")),
         );
+        app.add_system_set(
+            SystemSet::on_update(explore_mode)
+                .with_system(on_choice_event.after("gui-update").after("player-move")),
+        );
     }
 }
 
@@ -81,6 +100,20 @@ fn update_energy_text(
     let value = stat.value.to_string();
     text.sections[0].value = format!("{value}/{max}");
 }
+fn update_cargo_text(
+    stats_query: Query<&CargoRecource, With<Player>>,
+    mut text_query: Query<&mut Text, With<CargoText>>,
+) {
+    let stat = stats_query.single();
+    let mut text = text_query.single_mut();
+
+    if text.sections.len() == 0 {
+        return;
+    }
+    let max = stat.max.to_string();
+    let value = stat.value.to_string();
+    text.sections[0].value = format!("{value}/{max}");
+}
 
 ////////////////////////////////
 /// Handle Exploration Events
@@ -91,6 +124,7 @@ fn on_end_hex_event(
     mut game_over: EventWriter<GameOverEvent>,
     mut end_hex_event: EventReader<EndHexEvent>,
     mut grid: ResMut<Grid>,
+    registry: Res<EventRegistry>,
     player_state: Res<PlayerState>,
     mut player_query: Query<(&mut Player, &mut HealthRecource, &mut EnergyRecource)>,
 ) {
@@ -99,15 +133,12 @@ fn on_end_hex_event(
         let (mut player, mut health, mut energy) = player_query.single_mut();
         let hex = grid.get_hex(player_state.position);
 
-        if !ev.enter && EventKey::Mining == grid.get_event_key(hex) {
-            let event = grid.get_event_mining();
-            health.value = health.value + event.material;
-            health.value = health.value.clamp(0, health.max);
-        }
-        if !ev.enter && EventKey::Energy == grid.get_event_key(hex) {
-            let event = grid.get_event_energy();
-            energy.value = energy.value + event.energy;
-            energy.value = energy.value.clamp(0, energy.max);
+        if !ev.enter {
+            let mut ctx = ResolveContext {
+                health: &mut *health,
+                energy: &mut *energy,
+            };
+            registry.resolve(&mut ctx, &grid.get_event_action());
         }
 
         if let Some(entity) = grid.clr_node(&hex) {
@@ -120,16 +151,23 @@ fn on_end_hex_event(
 fn on_start_hex_event(
     mut commands: Commands,
     assets: Res<AppAssets>,
-    events: Res<GridEvents>,
+    registry: Res<EventRegistry>,
+    story: Res<StoryState>,
     mut grid: ResMut<Grid>,
     mut player_state: ResMut<PlayerState>,
     mut start_hex_event: EventReader<StartHexEvent>,
-    mut player_query: Query<(&mut Player, &Transform)>,
+    mut player_query: Query<(
+        &mut Player,
+        &Transform,
+        &HealthRecource,
+        &EnergyRecource,
+        &CargoRecource,
+    )>,
     dialog_query: Query<Entity, With<HudDialog>>,
     navigate_query: Query<Entity, With<HudNavigate>>,
 ) {
     for ev in start_hex_event.iter() {
-        let (mut player, transform) = player_query.single_mut();
+        let (mut player, transform, health, energy, cargo) = player_query.single_mut();
         player.active = false;
         player_state.position = Vec2 {
             x: transform.translation.x,
@@ -138,58 +176,65 @@ fn on_start_hex_event(
         let dialog = dialog_query.single();
         let navigate = navigate_query.single();
 
-        // FixMe This is messy and does not scale
         let hex = grid.get_hex(player_state.position);
-        match grid.get_event_key(hex) {
-            EventKey::None => grid.clr_event(),
-            EventKey::Combat => grid.set_event_combat(events.roll_combat_table(ev.seed)),
-            EventKey::Energy => grid.set_event_energy(events.roll_energy_table(ev.seed)),
-            EventKey::Mining => grid.set_event_mining(events.roll_mining_table(ev.seed)),
+        grid.visit(&hex);
+
+        // Note: Rare events grow towards the rim and with every cleared event
+        let modifier = RollModifier::new(
+            hex.distance(&Axial { q: 0, r: 0 }) as f32 / grid.hexmap.radius as f32,
+            player.total as f32 / DIFFICULTY_EVENTS,
+        );
+        // Note: Story events are placed by hand, everything else is rolled
+        let key = grid.get_event_key(hex);
+        let event = match grid.story.get(&hex) {
+            Some(event) => Some(event.clone()),
+            None => registry.roll(key, ev.seed, &modifier),
+        };
+        match event {
+            Some(event) => grid.set_event(event),
+            None => grid.clr_event(),
         };
 
-        handle_enter_hex_event(&mut commands, &grid, &assets, dialog, navigate);
+        let act = registry.actions(grid.active_key());
+        let stats = PlayerStats::new(health, energy, cargo);
+        handle_enter_hex_event(
+            &mut commands,
+            &grid,
+            act,
+            &stats,
+            &story,
+            &assets,
+            dialog,
+            navigate,
+        );
     }
 }
 
 fn handle_enter_hex_event(
     commands: &mut Commands,
     grid: &ResMut<Grid>,
+    act: EventAction,
+    stats: &PlayerStats,
+    story: &StoryState,
     assets: &Res<AppAssets>,
     dialog: Entity,
     navigate: Entity,
 ) {
-    let act = GridEvents::get_actions(grid.key);
     let data = grid.get_event_data();
     log::info!(data.title);
 
-    let title = commands
-        .spawn_bundle(TextBundle::from_section(
-            data.title,
-            TextStyle {
-                font: assets.gui_font.clone(),
-                font_size: 40.0,
-                color: gui::TEXT_BUTTON,
-            },
-        ))
-        .insert(HudCleanup)
-        .id();
-    let descr = commands
-        .spawn_bundle(TextBundle::from_section(
-            data.descr,
-            TextStyle {
-                font: assets.gui_font.clone(),
-                font_size: 20.0,
-                color: gui::TEXT_BUTTON,
-            },
-        ))
-        .insert(HudCleanup)
-        .id();
+    // Note: Events with choices replace the enter and leave buttons
+    if let Some(page) = data.pages.first() {
+        spawn_event_page(commands, page, stats, story, assets, dialog);
+        return;
+    }
 
+    let (title, descr) = spawn_dialog_text(commands, assets, &data.title, &data.descr);
     commands.entity(dialog).push_children(&[descr, title]);
 
     // FixMe Ugly cheat to fix empty events
     let mut text = act.leave;
-    if !data.enter && grid.key == EventKey::Combat {
+    if !data.enter && grid.active_key() == EventKey::Combat {
         text = "leave".to_string();
     }
     let leave = gui::create_button(
@@ -224,6 +269,200 @@ fn handle_enter_hex_event(
     }
 }
 
+fn spawn_dialog_text(
+    commands: &mut Commands,
+    assets: &Res<AppAssets>,
+    title: &str,
+    descr: &str,
+) -> (Entity, Entity) {
+    let title = commands
+        .spawn_bundle(TextBundle::from_section(
+            title,
+            TextStyle {
+                font: assets.gui_font.clone(),
+                font_size: 40.0,
+                color: gui::TEXT_BUTTON,
+            },
+        ))
+        .insert(HudCleanup)
+        .id();
+    let descr = commands
+        .spawn_bundle(TextBundle::from_section(
+            descr,
+            TextStyle {
+                font: assets.gui_font.clone(),
+                font_size: 20.0,
+                color: gui::TEXT_BUTTON,
+            },
+        ))
+        .insert(HudCleanup)
+        .id();
+    (title, descr)
+}
+
+fn spawn_event_page(
+    commands: &mut Commands,
+    page: &EventPage,
+    stats: &PlayerStats,
+    story: &StoryState,
+    assets: &Res<AppAssets>,
+    dialog: Entity,
+) {
+    let (title, descr) = spawn_dialog_text(commands, assets, &page.title, &page.descr);
+
+    // Note: The dialog stacks bottom up, the last choice goes in first
+    let mut list = Vec::new();
+    for (index, choice) in page.choices.iter().enumerate().rev() {
+        if choice.is_hidden(stats, story) {
+            continue;
+        }
+        list.push(gui::create_button(
+            commands,
+            gui::TEXT_BUTTON,
+            gui::NORMAL_BUTTON,
+            CHOICE_WIDTH,
+            true,
+            choice.text(stats, story),
+            assets.gui_font.clone(),
+            ButtonType {
+                key: ButtonKey::Choice(index),
+            },
+        ));
+    }
+    list.push(descr);
+    list.push(title);
+
+    commands.entity(dialog).push_children(&list);
+}
+
+fn on_choice_event(
+    mut commands: Commands,
+    assets: Res<AppAssets>,
+    mut grid: ResMut<Grid>,
+    mut story: ResMut<StoryState>,
+    mut state: ResMut<State<AppState>>,
+    player_state: Res<PlayerState>,
+    mut choice_event: EventReader<ChoiceEvent>,
+    mut end_hex_event: EventWriter<EndHexEvent>,
+    mut game_over: EventWriter<GameOverEvent>,
+    mut player_query: Query<
+        (&mut HealthRecource, &mut EnergyRecource, &mut CargoRecource),
+        With<Player>,
+    >,
+    dialog_query: Query<(Entity, Option<&Children>), With<HudDialog>>,
+) {
+    for ev in choice_event.iter() {
+        let data = grid.get_event_data();
+        let choice = match data.pages.get(grid.page) {
+            Some(page) => match page.choices.get(ev.index) {
+                Some(choice) => choice.clone(),
+                None => continue,
+            },
+            None => continue,
+        };
+
+        let (mut health, mut energy, mut cargo) = player_query.single_mut();
+        let stats = PlayerStats::new(&health, &energy, &cargo);
+        if !choice.is_open(&stats, &story) {
+            log::info!("on_choice_event: {} is locked", choice.label);
+            continue;
+        }
+
+        let (dialog, children) = dialog_query.single();
+        if let Some(children) = children {
+            for entity in children {
+                commands.entity(entity.clone()).despawn_recursive();
+            }
+        }
+
+        let mut combat = None;
+        let mut next = None;
+        for outcome in choice.outcomes.iter() {
+            match outcome {
+                Outcome::Gain(stat, value) => {
+                    change_stat(*stat, *value as i32, &mut health, &mut energy, &mut cargo)
+                }
+                Outcome::Lose(stat, value) => change_stat(
+                    *stat,
+                    -(*value as i32),
+                    &mut health,
+                    &mut energy,
+                    &mut cargo,
+                ),
+                Outcome::Combat(action) => combat = Some(*action),
+                Outcome::Reveal(radius) => {
+                    let hex = grid.get_hex(player_state.position);
+                    grid.scan(&hex, *radius);
+                }
+                Outcome::SetFlag(flag) => story.set_flag(flag),
+                Outcome::ClrFlag(flag) => story.clr_flag(flag),
+                Outcome::Count(name, value) => story.add_counter(name, *value),
+                Outcome::Page(page) => next = Some(*page),
+            }
+        }
+
+        if health.value < 1 {
+            game_over.send(GameOverEvent {
+                message: "Your ship was destroyed.".to_string(),
+            });
+            continue;
+        }
+        if energy.value < 1 {
+            game_over.send(GameOverEvent {
+                message: "No more energy, your ship is stranded.".to_string(),
+            });
+            continue;
+        }
+
+        // Note: Choice outcomes replace the passive resolve of the event
+        match (combat, next) {
+            (Some(action), _) => {
+                grid.set_event(EventInfo {
+                    data: EventData {
+                        key: EventKey::Combat,
+                        pages: Vec::new(),
+                        ..data
+                    },
+                    action: EventPayload::Combat(action),
+                });
+                state
+                    .push(AppState::GamePlay(GameMode::EventGrid))
+                    .unwrap_or_else(|error| log::error!("on_choice_event: {}", error));
+                end_hex_event.send(EndHexEvent { enter: true });
+            }
+            (None, Some(page)) => {
+                grid.page = page;
+                let stats = PlayerStats::new(&health, &energy, &cargo);
+                spawn_event_page(
+                    &mut commands,
+                    &data.pages[page],
+                    &stats,
+                    &story,
+                    &assets,
+                    dialog,
+                );
+            }
+            (None, None) => end_hex_event.send(EndHexEvent { enter: true }),
+        }
+    }
+}
+
+// Note: Stats stay between zero and the ship limits
+fn change_stat(
+    stat: Stat,
+    delta: i32,
+    health: &mut HealthRecource,
+    energy: &mut EnergyRecource,
+    cargo: &mut CargoRecource,
+) {
+    let (value, max) = match stat {
+        Stat::Health => (&mut health.value, health.max),
+        Stat::Energy => (&mut energy.value, energy.max),
+        Stat::Cargo => (&mut cargo.value, cargo.max),
+    };
+    *value = (*value as i32 + delta).clamp(0, max as i32) as u16;
+}
+
 ////////////////////////
 /// Gamehud Extentions
 ////////////////////////
@@ -310,6 +549,38 @@ pub(crate) fn explore_mode_stats(commands: &mut Commands, font: &Handle<Font>) -
                         ))
                         .insert(EnergyText);
                 });
+            parent
+                .spawn_bundle(NodeBundle {
+                    style: Style {
+                        flex_direction: FlexDirection::Row,
+                        size: Size::new(Val::Px(TILE_SIZE * 2.2), Val::Px(65.0)),
+                        justify_content: JustifyContent::SpaceBetween,
+                        align_items: AlignItems::Center,
+                        ..Default::default()
+                    },
+                    color: Color::rgba(0., 0., 0., 0.0).into(),
+                    ..Default::default()
+                })
+                .with_children(|parent| {
+                    parent.spawn_bundle(TextBundle::from_section(
+                        "cargo:",
+                        TextStyle {
+                            font: font.clone(),
+                            font_size: 24.0,
+                            color: gui::TEXT_BUTTON,
+                        },
+                    ));
+                    parent
+                        .spawn_bundle(TextBundle::from_section(
+                            format!("0/{}", CARGO_CAPACITY),
+                            TextStyle {
+                                font: font.clone(),
+                                font_size: 24.0,
+                                color: gui::TEXT_BUTTON,
+                            },
+                        ))
+                        .insert(CargoText);
+                });
         })
         .id();
 
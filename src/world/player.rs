@@ -4,6 +4,13 @@ pub mod resources;
 pub use resources::*;
 
 pub const ENERGY_COST: i32 = 4;
+pub const MAX_ROUTE_JUMPS: i32 = 4;
+
+pub const SENSOR_RANGE: i32 = 6;
+pub const SCAN_RANGE: i32 = 12;
+pub const SCAN_COST: u16 = 8;
+
+pub const CARGO_CAPACITY: u16 = 60;
 
 #[derive(Component, Default, Inspectable)]
 pub struct Player {
@@ -64,6 +71,7 @@ impl Plugin for PlayerPlugin {
             app.register_inspectable::<Player>();
             app.register_inspectable::<HealthRecource>();
             app.register_inspectable::<EnergyRecource>();
+            app.register_inspectable::<CargoRecource>();
         }
 
         app.add_system_set(
@@ -108,6 +116,18 @@ impl Plugin for PlayerPlugin {
                 .with_system(move_explore_grid.after("gui-update").label("player-move")),
         );
 
+        app.add_system_set(
+            SystemSet::on_update(explore_mode)
+                .with_system(update_sensors.after("player-move").label("player-sensor")),
+        );
+        app.add_system_set(
+            SystemSet::on_update(explore_mode).with_system(
+                scan_explore_grid
+                    .after("gui-update")
+                    .before("player-sensor"),
+            ),
+        );
+
         app.add_system_set(
             SystemSet::on_update(event_mode)
                 .with_system(move_player_camera.after("gui-update").after("player-move")),
@@ -272,7 +292,11 @@ fn enter_player_explore(
             move_speed: ship_info.speed,
             rotate_speed: ROTATE_SPEED,
             ..Default::default()
-        });
+        })
+        .insert(Collider {
+            radius: TILE_SIZE * 0.25,
+        })
+        .insert(Loadout::default());
 
     // Resource Setup
     player.insert(HealthRecource {
@@ -283,6 +307,10 @@ fn enter_player_explore(
         max: ship_info.energy * 10,
         value: ship_info.energy * 10,
     });
+    player.insert(CargoRecource {
+        max: CARGO_CAPACITY,
+        value: 0,
+    });
 
     // Movement Setup
     player
@@ -295,6 +323,7 @@ fn enter_player_explore(
             cost: 0_25,
             speed: 6_00,
             distance: 4_00,
+            route: Vec::new(),
         });
 }
 
@@ -351,21 +380,24 @@ fn move_explore_grid(
     grid: Res<Grid>,
     time: Res<Time>,
     windows: Res<Windows>,
-    mut shift: ResMut<Shift64>,
+    mut streams: ResMut<RngStreams>,
+    mut preview: ResMut<JumpPreview>,
     mut game_over: EventWriter<GameOverEvent>,
     mut buttons: ResMut<Input<MouseButton>>,
     mut hex_event: EventWriter<StartHexEvent>,
     mut player_query: Query<(
         &mut Player,
         &mut GridTarget,
+        &mut GridMovement,
         &mut Transform,
         &mut EnergyRecource,
     )>,
-    mut active_query: Query<(&mut Sprite, &mut Transform), (With<GridTargetHex>, Without<Player>)>,
     camera_query: Query<(&Camera, &GlobalTransform), (With<PlayerCamera>, Without<Player>)>,
 ) {
-    let (mut player, mut move_to, mut transform, mut energy) = player_query.single_mut();
+    let (mut player, mut move_to, mut movement, mut transform, mut energy) =
+        player_query.single_mut();
     if !player.active {
+        preview.clear();
         return;
     }
 
@@ -378,56 +410,53 @@ fn move_explore_grid(
     };
 
     // Update the current target
-    move_to.update_current(window, camera, camera_transform);
+    move_to.update_current(window, camera, camera_transform, &grid.hexmap.layout);
 
     let pos = Vec2 {
         x: transform.translation.x,
         y: transform.translation.y,
     };
 
-    // Update the path to the targets Not sure if this is the way
-    // It is here because we compute the mouse position in move to
-    let (mut active_sprite, mut active_transform) = active_query.single_mut();
-    active_transform.translation.x = move_to.mouse.x;
-    active_transform.translation.y = move_to.mouse.y;
-
     // Note: Duplicated/Revesed math here
     // see move_to.update_current call above
-    let hex = &grid.layout.hex_for(move_to.mouse);
-    let dist = hex.distance(&grid.layout.hex_for(pos));
-    let cost = (dist * ENERGY_COST) as u16;
-
-    let can_jump = dist <= player.jump_range as i32;
+    if move_to.moving || !movement.is_idle() {
+        preview.clear();
+    } else {
+        let from = grid.hexmap.layout.hex_for(pos);
+        let hex = grid.hexmap.layout.hex_for(move_to.mouse);
+        preview.plan(&grid, from, hex, player.jump_range as i32, energy.value);
+    }
 
     // Note: There is no need to rest this
-    if can_jump && !move_to.moving && energy.value >= cost && grid.on_grid(hex) {
-        active_sprite.color = Color::rgb(1., 1., 1.);
-        if buttons.just_pressed(MouseButton::Left) {
-            log::info!("... event move ...");
-            // FixMe: Start roling event dice,
-            // cuting corners no dice animations.
-
-            move_to.moving = true;
-            move_to.set_current();
-            buttons.clear();
-
-            // Note Cleanup, Subtract cost
-
-            if energy.value > cost {
-                energy.value = energy.value - cost;
-            } else {
-                player.active = false;
-                game_over.send(GameOverEvent {
-                    message: "No more energy, your ship is stranded.".to_string(),
-                })
-            }
-        }
-    } else {
-        active_sprite.color = Color::rgb(1., 0., 0.);
+    if preview.is_ready() && buttons.just_pressed(MouseButton::Left) {
+        log::info!("... event move ...");
+        // FixMe: Start roling event dice,
+        // cuting corners no dice animations.
+
+        movement.set_route(preview.jumps.clone());
+        buttons.clear();
     }
 
-    // TODO Move Above or replace with method call below
-    // grid_movement.update_current(&active_entity, &move_to, &transform);
+    // Note: Events on the way may drain energy, drop a route that can not be finished
+    if !move_to.moving
+        && !movement.is_idle()
+        && !can_afford(movement.route_energy(ENERGY_COST), energy.value)
+    {
+        log::info!("... route dropped, not enough energy ...");
+        movement.route.clear();
+    }
+
+    // Start the next jump on the route, the cost is paid per jump
+    if let Some(jump) = movement.update_current(&mut move_to, &grid.hexmap.layout) {
+        let cost = (jump.cost * ENERGY_COST) as u16;
+        energy.value = energy.value.saturating_sub(cost);
+        if energy.value < 1 {
+            player.active = false;
+            game_over.send(GameOverEvent {
+                message: "No more energy, your ship is stranded.".to_string(),
+            })
+        }
+    }
 
     if move_to.target.distance(pos) > 0.25 {
         let distance = move_to.target - pos;
@@ -445,11 +474,50 @@ fn move_explore_grid(
     } else if move_to.moving {
         move_to.moving = false;
         hex_event.send(StartHexEvent {
-            seed: shift.shift(),
+            seed: streams.event.shift(),
         });
     }
 }
 
+fn update_sensors(mut grid: ResMut<Grid>, player_query: Query<&Transform, With<Player>>) {
+    let transform = player_query.single();
+    let hex = grid.hexmap.layout.hex_for(Vec2 {
+        x: transform.translation.x,
+        y: transform.translation.y,
+    });
+
+    // Note: Only touch the grid when needed, it triggers the fog update
+    if grid.sensor != Some(hex) {
+        grid.update_sensors(&hex, SENSOR_RANGE);
+    }
+}
+
+fn scan_explore_grid(
+    mut grid: ResMut<Grid>,
+    keyboard: Res<Input<KeyCode>>,
+    mut player_query: Query<(&Player, &GridTarget, &Transform, &mut EnergyRecource)>,
+) {
+    let (player, move_to, transform, mut energy) = player_query.single_mut();
+    if !player.active || move_to.moving || !keyboard.just_pressed(KeyCode::Space) {
+        return;
+    }
+
+    let hex = grid.hexmap.layout.hex_for(Vec2 {
+        x: transform.translation.x,
+        y: transform.translation.y,
+    });
+
+    // Note: A scan never strands the ship, it needs energy to spare
+    if energy.value <= SCAN_COST {
+        log::info!("... scan {} needs more than {} energy ...", hex, SCAN_COST);
+        return;
+    }
+
+    log::info!("... scan {} ...", hex);
+    energy.value = energy.value - SCAN_COST;
+    grid.scan(&hex, SCAN_RANGE);
+}
+
 fn move_player_camera(
     player_query: Query<(&Player, &Transform)>,
     mut camera_offset: ResMut<CameraOffset>,
@@ -11,3 +11,9 @@ pub struct EnergyRecource {
     pub max: u16,
     pub value: u16,
 }
+
+#[derive(Component, Clone, Copy, Debug, Hash, Inspectable)]
+pub struct CargoRecource {
+    pub max: u16,
+    pub value: u16,
+}
@@ -0,0 +1,355 @@
+use bevy::asset::{AssetLoader, LoadContext, LoadedAsset};
+use bevy::reflect::TypeUuid;
+use bevy::utils::BoxedFuture;
+use serde::Deserialize;
+
+use super::*;
+
+// Narrative state of the current run, events read and write it
+#[derive(Default, Clone, Debug)]
+pub struct StoryState {
+    pub flags: HashSet<String>,
+    pub counters: HashMap<String, u16>,
+    pub chapter: usize,
+    pub progress: u16, // Events cleared in the current chapter
+    pub placed: HashSet<String>,
+}
+
+// Hand placed event, shows up on its hex once the requirements are met
+#[derive(Clone, Debug, Deserialize)]
+pub struct StoryEvent {
+    pub id: String,
+    pub hex: (i32, i32),
+    #[serde(default)]
+    pub requires: Vec<Condition>,
+    pub event: EventEntry,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct StoryChapter {
+    pub title: String,
+    #[serde(default)]
+    pub region: Option<i32>, // Radius the ship may travel, the whole galaxy if not set
+    pub goal: Vec<Condition>,
+    #[serde(default)]
+    pub ending: Option<String>,
+    #[serde(default)]
+    pub events: Vec<StoryEvent>,
+}
+
+#[derive(Clone, Debug, Deserialize, TypeUuid)]
+#[uuid = "8b1e6a52-4c0f-4d7a-9e3b-5f2c7d1a0e94"]
+pub struct StoryArc {
+    pub chapters: Vec<StoryChapter>,
+}
+
+// What checking the goal of the current chapter led to
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum StoryStep {
+    Pending,
+    Advanced,
+    Ending(String),
+}
+
+#[derive(Default)]
+pub struct StoryLoader;
+
+pub struct StoryPlugin;
+
+impl Plugin for StoryPlugin {
+    fn build(&self, app: &mut App) {
+        let base_mode = AppState::GamePlay(GameMode::BaseGrid);
+        let explore_mode = AppState::GamePlay(GameMode::ExploreGrid);
+
+        app.add_asset::<StoryArc>();
+        app.init_asset_loader::<StoryLoader>();
+
+        app.init_resource::<StoryState>();
+        app.add_system_set(SystemSet::on_enter(base_mode).with_system(reset_story));
+        app.add_system_set(
+            SystemSet::on_update(explore_mode).with_system(update_story.after("gui-update")),
+        );
+    }
+}
+
+impl AssetLoader for StoryLoader {
+    fn load<'a>(
+        &'a self,
+        bytes: &'a [u8],
+        load_context: &'a mut LoadContext,
+    ) -> BoxedFuture<'a, Result<(), anyhow::Error>> {
+        Box::pin(async move {
+            let path = load_context.path().display().to_string();
+            let arc = ron::de::from_bytes::<StoryArc>(bytes)
+                .map_err(|e| anyhow::anyhow!("{}: {}", path, e))?;
+            arc.validate()
+                .map_err(|e| anyhow::anyhow!("{}: {}", path, e))?;
+
+            load_context.set_default_asset(LoadedAsset::new(arc));
+            Ok(())
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["story.ron"]
+    }
+}
+
+// Note: Every run starts a fresh story
+fn reset_story(mut story: ResMut<StoryState>) {
+    log::info!("reset_story");
+    *story = StoryState::default();
+}
+
+// Completes the chapter once its goal is met, the last one ends the run
+fn update_story(
+    arcs: Res<Assets<StoryArc>>,
+    world_assets: Res<WorldAssets>,
+    mut story: ResMut<StoryState>,
+    mut grid: ResMut<Grid>,
+    mut game_over: EventWriter<GameOverEvent>,
+    mut player_query: Query<(
+        &mut Player,
+        &HealthRecource,
+        &EnergyRecource,
+        &CargoRecource,
+    )>,
+) {
+    let arc = match arcs.get(&world_assets.story) {
+        Some(arc) => arc,
+        None => return,
+    };
+
+    // Note: Only touch the grid on change, the fog redraws on every change
+    let region = arc.region(&story, grid.hexmap.radius);
+    if grid.region != region {
+        grid.region = region;
+    }
+
+    let (mut player, health, energy, cargo) = player_query.single_mut();
+    if !player.active {
+        return;
+    }
+    let stats = PlayerStats::new(health, energy, cargo);
+    if let StoryStep::Ending(ending) = arc.step(&mut story, &stats) {
+        player.active = false;
+        game_over.send(GameOverEvent { message: ending });
+    }
+}
+
+impl StoryState {
+    pub fn has_flag(&self, flag: &str) -> bool {
+        self.flags.contains(flag)
+    }
+
+    pub fn set_flag(&mut self, flag: &str) {
+        self.flags.insert(flag.to_string());
+    }
+
+    pub fn clr_flag(&mut self, flag: &str) {
+        self.flags.remove(flag);
+    }
+
+    pub fn counter(&self, name: &str) -> u16 {
+        match self.counters.get(name) {
+            Some(value) => *value,
+            None => 0,
+        }
+    }
+
+    pub fn add_counter(&mut self, name: &str, value: u16) {
+        let counter = self.counters.entry(name.to_string()).or_insert(0);
+        *counter = counter.saturating_add(value);
+    }
+
+    pub fn advance(&mut self) {
+        self.chapter += 1;
+        self.progress = 0;
+    }
+}
+
+impl StoryArc {
+    // Radius the ship may travel in the current chapter
+    pub fn region(&self, story: &StoryState, radius: i32) -> i32 {
+        match self.chapters.get(story.chapter) {
+            Some(chapter) => chapter.region.unwrap_or(radius),
+            None => radius,
+        }
+    }
+
+    // Completes the current chapter once its goal is met
+    pub fn step(&self, story: &mut StoryState, stats: &PlayerStats) -> StoryStep {
+        let chapter = match self.chapters.get(story.chapter) {
+            Some(chapter) => chapter,
+            None => return StoryStep::Pending,
+        };
+        if !chapter.goal.iter().all(|c| c.check(stats, story)) {
+            return StoryStep::Pending;
+        }
+
+        log::info!("update_story: completed {}", chapter.title);
+        match &chapter.ending {
+            Some(ending) => StoryStep::Ending(ending.clone()),
+            None => {
+                story.advance();
+                StoryStep::Advanced
+            }
+        }
+    }
+
+    // Note: Story flags are only checked against the story itself,
+    // a flag set by a random event may never happen in a run
+    pub fn validate(&self) -> Result<(), String> {
+        if self.chapters.is_empty() {
+            return Err("story has no chapters".to_string());
+        }
+
+        let mut flags = HashSet::new();
+        for chapter in self.chapters.iter() {
+            for event in chapter.events.iter() {
+                for choice in event.event.all_choices() {
+                    for outcome in choice.outcomes.iter() {
+                        if let Outcome::SetFlag(flag) = outcome {
+                            flags.insert(flag.clone());
+                        }
+                    }
+                }
+            }
+        }
+
+        let mut ids = HashSet::new();
+        for (idx, chapter) in self.chapters.iter().enumerate() {
+            let name = format!("chapter {} ({:?})", idx, chapter.title);
+            if chapter.goal.is_empty() {
+                return Err(format!("{}: goal is empty", name));
+            }
+            if chapter.ending.is_some() != (idx + 1 == self.chapters.len()) {
+                return Err(format!("{}: only the last chapter has an ending", name));
+            }
+            if let Some(region) = chapter.region {
+                if region < 1 {
+                    return Err(format!("{}: region must be above 0", name));
+                }
+            }
+
+            let mut conditions: Vec<&Condition> = chapter.goal.iter().collect();
+            for event in chapter.events.iter() {
+                conditions.extend(event.requires.iter());
+                for choice in event.event.all_choices() {
+                    conditions.extend(choice.conditions.iter());
+                }
+            }
+            for condition in conditions {
+                match condition {
+                    Condition::Flag(flag) | Condition::NotFlag(flag) if !flags.contains(flag) => {
+                        return Err(format!("{}: flag {:?} is never set", name, flag));
+                    }
+                    Condition::Chapter(chapter) if *chapter >= self.chapters.len() => {
+                        return Err(format!("{}: there is no chapter {}", name, chapter));
+                    }
+                    _ => {}
+                }
+            }
+
+            for event in chapter.events.iter() {
+                let entry = &event.event;
+                if !ids.insert(event.id.clone()) {
+                    return Err(format!("{}: event id {:?} is used twice", name, event.id));
+                }
+                if entry.action.key() != EventKey::Story {
+                    return Err(format!("{}: {:?} needs a Story action", name, event.id));
+                }
+                if entry.choices.is_empty() {
+                    return Err(format!("{}: {:?} has no choices", name, event.id));
+                }
+                entry
+                    .validate_pages()
+                    .map_err(|e| format!("{}: {:?}: {}", name, event.id, e))?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl StoryEvent {
+    pub fn hex(&self) -> Axial {
+        Axial {
+            q: self.hex.0,
+            r: self.hex.1,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Condition, PlayerStats, StoryArc, StoryState, StoryStep};
+
+    fn main_story() -> StoryArc {
+        let source = include_str!("../../assets/story/main.story.ron");
+        ron::from_str::<StoryArc>(source).unwrap()
+    }
+
+    #[test]
+    fn story_shipped() {
+        let arc = main_story();
+        assert!(arc.validate().is_ok(), "{:?}", arc.validate());
+        assert!(arc.chapters.last().unwrap().ending.is_some());
+    }
+
+    #[test]
+    fn story_bad_references() {
+        let mut arc = main_story();
+        arc.chapters[0].goal.push(Condition::Chapter(9));
+        let err = arc.validate().unwrap_err();
+        assert!(
+            err.contains("chapter 0") && err.contains("no chapter 9"),
+            "{}",
+            err
+        );
+
+        let mut arc = main_story();
+        let last = arc.chapters.len() - 1;
+        arc.chapters[last]
+            .goal
+            .push(Condition::Flag("lost-fleet".to_string()));
+        let err = arc.validate().unwrap_err();
+        assert!(err.contains("lost-fleet"), "{}", err);
+
+        let mut arc = main_story();
+        let event = &mut arc.chapters[0].events[0];
+        event.requires.push(Condition::NotFlag("typo".to_string()));
+        assert!(arc.validate().unwrap_err().contains("typo"));
+    }
+
+    #[test]
+    fn story_step() {
+        let arc = main_story();
+        let stats = PlayerStats::default();
+        let mut story = StoryState::default();
+        let first = arc.chapters[0].region.unwrap();
+        let second = arc.chapters[1].region.unwrap();
+        assert!(first < second);
+
+        assert_eq!(arc.region(&story, 38), first);
+        assert_eq!(arc.step(&mut story, &stats), StoryStep::Pending);
+        assert_eq!(story.chapter, 0);
+
+        story.set_flag("signal-traced");
+        story.progress = 3;
+        assert_eq!(arc.step(&mut story, &stats), StoryStep::Advanced);
+        assert_eq!(story.chapter, 1);
+        assert_eq!(story.progress, 0);
+        assert_eq!(arc.region(&story, 38), second);
+
+        // The last chapter ends the run with its message
+        let last = arc.chapters.len() - 1;
+        story.chapter = last;
+        story.set_flag("home-found");
+        let ending = arc.chapters[last].ending.clone().unwrap();
+        assert_eq!(arc.step(&mut story, &stats), StoryStep::Ending(ending));
+        assert_eq!(
+            arc.region(&story, 38),
+            arc.chapters[last].region.unwrap_or(38)
+        );
+    }
+}